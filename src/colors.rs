@@ -1,5 +1,7 @@
 //! Common color structures used in vga programming.
 
+use core::convert::TryFrom;
+
 /// Represents the size of the vga palette in bytes.
 pub const PALETTE_SIZE: usize = 768;
 
@@ -41,18 +43,200 @@ pub enum Color16 {
     White = 0xF,
 }
 
+impl Color16 {
+    /// Every `Color16`, ordered by value from `0x0` to `0xF`.
+    pub const ALL: [Color16; 16] = [
+        Color16::Black,
+        Color16::Blue,
+        Color16::Green,
+        Color16::Cyan,
+        Color16::Red,
+        Color16::Magenta,
+        Color16::Brown,
+        Color16::LightGrey,
+        Color16::DarkGrey,
+        Color16::LightBlue,
+        Color16::LightGreen,
+        Color16::LightCyan,
+        Color16::LightRed,
+        Color16::Pink,
+        Color16::Yellow,
+        Color16::White,
+    ];
+
+    /// Returns an iterator over every `Color16`, ordered by value.
+    pub fn all() -> impl Iterator<Item = Color16> {
+        Color16::ALL.iter().copied()
+    }
+
+    /// Returns the `Color16` represented by `value`, or `None` if
+    /// `value` is greater than `0xF`.
+    pub const fn from_nibble(value: u8) -> Option<Color16> {
+        match value {
+            0x0 => Some(Color16::Black),
+            0x1 => Some(Color16::Blue),
+            0x2 => Some(Color16::Green),
+            0x3 => Some(Color16::Cyan),
+            0x4 => Some(Color16::Red),
+            0x5 => Some(Color16::Magenta),
+            0x6 => Some(Color16::Brown),
+            0x7 => Some(Color16::LightGrey),
+            0x8 => Some(Color16::DarkGrey),
+            0x9 => Some(Color16::LightBlue),
+            0xA => Some(Color16::LightGreen),
+            0xB => Some(Color16::LightCyan),
+            0xC => Some(Color16::LightRed),
+            0xD => Some(Color16::Pink),
+            0xE => Some(Color16::Yellow),
+            0xF => Some(Color16::White),
+            _ => None,
+        }
+    }
+
+    /// Returns the canonical 24 bit `(red, green, blue)` value of this
+    /// color, as listed in `COLOR16_RGB`.
+    pub const fn to_rgb(self) -> (u8, u8, u8) {
+        COLOR16_RGB[self as usize]
+    }
+}
+
+impl TryFrom<u8> for Color16 {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Color16::from_nibble(value).ok_or("Color16 only accepts values between 0x0-0xF!")
+    }
+}
+
 impl From<Color16> for u8 {
     fn from(value: Color16) -> u8 {
         value as u8
     }
 }
 
+/// The canonical 24 bit `(red, green, blue)` values of each `Color16`,
+/// indexed by the color's value.
+pub const COLOR16_RGB: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0x00, 0x00, 0xAA),
+    (0x00, 0xAA, 0x00),
+    (0x00, 0xAA, 0xAA),
+    (0xAA, 0x00, 0x00),
+    (0xAA, 0x00, 0xAA),
+    (0xAA, 0x55, 0x00),
+    (0xAA, 0xAA, 0xAA),
+    (0x55, 0x55, 0x55),
+    (0x55, 0x55, 0xFF),
+    (0x55, 0xFF, 0x55),
+    (0x55, 0xFF, 0xFF),
+    (0xFF, 0x55, 0x55),
+    (0xFF, 0x55, 0xFF),
+    (0xFF, 0xFF, 0x55),
+    (0xFF, 0xFF, 0xFF),
+];
+
+/// Returns the `Color16` closest to the given 24 bit color, as measured by
+/// the sum of squared differences against `COLOR16_RGB`.
+pub fn nearest_color16(red: u8, green: u8, blue: u8) -> Color16 {
+    let distance = |&(r, g, b): &(u8, u8, u8)| {
+        let dr = i32::from(r) - i32::from(red);
+        let dg = i32::from(g) - i32::from(green);
+        let db = i32::from(b) - i32::from(blue);
+        dr * dr + dg * dg + db * db
+    };
+    let (index, _) = COLOR16_RGB
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, rgb)| distance(rgb))
+        .unwrap();
+    // `COLOR16_RGB` has exactly 16 entries.
+    Color16::from_nibble(index as u8).unwrap()
+}
+
+/// Converts a 24 bit `(red, green, blue)` color to the 6 bits per component
+/// used by the dac, scaling `0xFF` to `0x3F` with rounding.
+pub const fn rgb888_to_dac(color: (u8, u8, u8)) -> (u8, u8, u8) {
+    const fn scale(component: u8) -> u8 {
+        ((component as u16 * 0x3F + 0x7F) / 0xFF) as u8
+    }
+    (scale(color.0), scale(color.1), scale(color.2))
+}
+
+/// Converts a 6 bits per component dac color to a 24 bit `(red, green, blue)`
+/// color, scaling `0x3F` to `0xFF` with rounding. Components above `0x3F`
+/// are masked like they are by the dac.
+pub const fn dac_to_rgb888(color: (u8, u8, u8)) -> (u8, u8, u8) {
+    const fn scale(component: u8) -> u8 {
+        (((component & 0x3F) as u16 * 0xFF + 0x1F) / 0x3F) as u8
+    }
+    (scale(color.0), scale(color.1), scale(color.2))
+}
+
+/// Returns a palette built from the 24 bit `colors`, converted with
+/// `rgb888_to_dac`. Entries past the end of `colors` are black, and any
+/// colors past the first 256 are ignored.
+pub fn palette_from_rgb888(colors: &[(u8, u8, u8)]) -> [u8; PALETTE_SIZE] {
+    let mut palette = [0; PALETTE_SIZE];
+    for (entry, color) in palette.chunks_exact_mut(3).zip(colors) {
+        let (red, green, blue) = rgb888_to_dac(*color);
+        entry.copy_from_slice(&[red, green, blue]);
+    }
+    palette
+}
+
+/// A lookup table mapping each 6 bit dac value to its value after a gamma
+/// correction of `1 / 2.2`, which brightens palettes that look too dark
+/// on modern displays. Pass it to `ColorPaletteRegisters::load_palette_gamma`.
+pub const GAMMA_2_2: [u8; 64] = [
+    0x00, 0x0A, 0x0D, 0x10, 0x12, 0x14, 0x16, 0x17, 0x19, 0x1A, 0x1B, 0x1C, 0x1E, 0x1F, 0x20, 0x21,
+    0x22, 0x23, 0x24, 0x25, 0x25, 0x26, 0x27, 0x28, 0x29, 0x29, 0x2A, 0x2B, 0x2C, 0x2C, 0x2D, 0x2E,
+    0x2E, 0x2F, 0x30, 0x30, 0x31, 0x31, 0x32, 0x33, 0x33, 0x34, 0x34, 0x35, 0x36, 0x36, 0x37, 0x37,
+    0x38, 0x38, 0x39, 0x39, 0x3A, 0x3A, 0x3B, 0x3B, 0x3C, 0x3C, 0x3D, 0x3D, 0x3E, 0x3E, 0x3F, 0x3F,
+];
+
+/// Returns a copy of `palette` with every color replaced by its luminance,
+/// using the weights `0.299` for red, `0.587` for green and `0.114` for blue.
+pub fn grayscale(palette: &[u8; PALETTE_SIZE]) -> [u8; PALETTE_SIZE] {
+    let mut grayscale = [0; PALETTE_SIZE];
+    for (gray, color) in grayscale.chunks_exact_mut(3).zip(palette.chunks_exact(3)) {
+        let luminance = (299 * u32::from(color[0])
+            + 587 * u32::from(color[1])
+            + 114 * u32::from(color[2])
+            + 500)
+            / 1000;
+        for component in gray.iter_mut() {
+            *component = luminance as u8;
+        }
+    }
+    grayscale
+}
+
+/// Returns the `DEFAULT_PALETTE` converted to grayscale.
+pub fn grayscale_palette() -> [u8; PALETTE_SIZE] {
+    grayscale(&DEFAULT_PALETTE)
+}
+
 /// Represents a color for vga text modes.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct TextModeColor(u8);
 
 impl TextModeColor {
+    /// `Color16::LightGrey` on `Color16::Black`, the default vga text color.
+    pub const LIGHT_GREY_ON_BLACK: TextModeColor =
+        TextModeColor::new(Color16::LightGrey, Color16::Black);
+    /// `Color16::White` on `Color16::Black`.
+    pub const WHITE_ON_BLACK: TextModeColor = TextModeColor::new(Color16::White, Color16::Black);
+    /// `Color16::Yellow` on `Color16::Black`.
+    pub const YELLOW_ON_BLACK: TextModeColor = TextModeColor::new(Color16::Yellow, Color16::Black);
+    /// `Color16::White` on `Color16::Blue`.
+    pub const WHITE_ON_BLUE: TextModeColor = TextModeColor::new(Color16::White, Color16::Blue);
+    /// `Color16::White` on `Color16::Red`.
+    pub const WHITE_ON_RED: TextModeColor = TextModeColor::new(Color16::White, Color16::Red);
+    /// `Color16::Black` on `Color16::LightGrey`.
+    pub const BLACK_ON_LIGHT_GREY: TextModeColor =
+        TextModeColor::new(Color16::Black, Color16::LightGrey);
+
     /// Returns a new `TextModeColor` given the specified `foreground`
     /// and `background` color.
     pub const fn new(foreground: Color16, background: Color16) -> TextModeColor {
@@ -66,7 +250,223 @@ impl TextModeColor {
 
     /// Sets the foreground color given the specified `foreground`.
     pub fn set_foreground(&mut self, foreground: Color16) {
-        self.0 = foreground as u8;
+        self.0 = (self.0 & 0xF0) | (foreground as u8);
+    }
+
+    /// Sets or clears the blink bit, which is the high bit `(0x80)` of the
+    /// attribute byte.
+    ///
+    /// The hardware uses this same bit for two different things, depending on
+    /// the mode set with `TextWriter::blink_enable` and `TextWriter::blink_disable`:
+    ///
+    /// - With blinking enabled (the default), the bit makes the character blink
+    ///   and only the background colors `Color16::Black` through `Color16::LightGrey`
+    ///   are available.
+    /// - With blinking disabled, the bit is the high bit of the background color,
+    ///   so all 16 background colors are available and nothing blinks.
+    ///
+    /// Since the bit is shared, setting a bright background such as
+    /// `Color16::DarkGrey` makes the character blink while blinking is enabled,
+    /// and `set_blink(true)` brightens the background while it's disabled.
+    pub fn set_blink(&mut self, blink: bool) {
+        if blink {
+            self.0 |= 0x80;
+        } else {
+            self.0 &= 0x7F;
+        }
+    }
+
+    /// Returns `true` if the blink bit of the `TextModeColor` is set.
+    pub fn is_blinking(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// Returns the background color of the `TextModeColor`.
+    pub fn background(self) -> Color16 {
+        // A nibble always maps to a valid `Color16`.
+        Color16::from_nibble(self.0 >> 4).unwrap()
+    }
+
+    /// Returns the foreground color of the `TextModeColor`.
+    pub fn foreground(self) -> Color16 {
+        // A nibble always maps to a valid `Color16`.
+        Color16::from_nibble(self.0 & 0x0F).unwrap()
+    }
+}
+
+impl Default for TextModeColor {
+    /// Returns `TextModeColor::LIGHT_GREY_ON_BLACK`.
+    fn default() -> TextModeColor {
+        TextModeColor::LIGHT_GREY_ON_BLACK
+    }
+}
+
+/// A single 6 bit `(0x00 -> 0x3F)` component of a dac color.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DacComponent(u8);
+
+impl DacComponent {
+    /// The largest value a `DacComponent` can hold.
+    pub const MAX: DacComponent = DacComponent(0x3F);
+
+    /// Returns a new `DacComponent` from the lower 6 bits of `value`,
+    /// the same way the dac itself ignores the upper 2 bits.
+    pub const fn new(value: u8) -> DacComponent {
+        DacComponent(value & 0x3F)
+    }
+
+    /// Returns a new `DacComponent` from `value`, saturating at `0x3F`.
+    pub const fn new_clamped(value: u8) -> DacComponent {
+        if value > 0x3F {
+            DacComponent::MAX
+        } else {
+            DacComponent(value)
+        }
+    }
+
+    /// Returns the value of the component, in the range `0x00 -> 0x3F`.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<DacComponent> for u8 {
+    fn from(component: DacComponent) -> u8 {
+        component.0
+    }
+}
+
+/// Represents a single color of the vga palette, with each component
+/// guaranteed to fit in the 6 bits `(0x00 -> 0x3F)` supported by the dac.
+///
+/// A `PaletteColor` can't hold values the dac would silently truncate,
+/// so the conversion from other ranges is explicit.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::colors::PaletteColor;
+/// use vga::vga::VGA;
+///
+/// let orange = PaletteColor::from_rgb888(0xFF, 0x80, 0x00);
+/// VGA.lock()
+///     .color_palette_registers
+///     .set_palette_color(16, orange);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PaletteColor {
+    /// The red component of the color.
+    pub red: DacComponent,
+    /// The green component of the color.
+    pub green: DacComponent,
+    /// The blue component of the color.
+    pub blue: DacComponent,
+}
+
+impl PaletteColor {
+    /// Returns a new `PaletteColor` from the lower 6 bits of the given `red`,
+    /// `green` and `blue` components, the same way the dac masks them.
+    pub const fn new(red: u8, green: u8, blue: u8) -> PaletteColor {
+        PaletteColor {
+            red: DacComponent::new(red),
+            green: DacComponent::new(green),
+            blue: DacComponent::new(blue),
+        }
+    }
+
+    /// Returns a new `PaletteColor` from 6 bit components, saturating any
+    /// component above `0x3F`.
+    pub const fn new_clamped(red: u8, green: u8, blue: u8) -> PaletteColor {
+        PaletteColor {
+            red: DacComponent::new_clamped(red),
+            green: DacComponent::new_clamped(green),
+            blue: DacComponent::new_clamped(blue),
+        }
+    }
+
+    /// Returns a new `PaletteColor` from a 24 bit color, scaling each
+    /// component from `0x00 -> 0xFF` to `0x00 -> 0x3F` with rounding.
+    pub const fn from_rgb888(red: u8, green: u8, blue: u8) -> PaletteColor {
+        let (red, green, blue) = rgb888_to_dac((red, green, blue));
+        PaletteColor::new(red, green, blue)
+    }
+
+    /// Returns the color as a 24 bit `(red, green, blue)` color.
+    pub const fn to_rgb888(self) -> (u8, u8, u8) {
+        dac_to_rgb888((self.red.0, self.green.0, self.blue.0))
+    }
+}
+
+impl From<(u8, u8, u8)> for PaletteColor {
+    fn from((red, green, blue): (u8, u8, u8)) -> PaletteColor {
+        PaletteColor::new(red, green, blue)
+    }
+}
+
+impl From<PaletteColor> for (u8, u8, u8) {
+    fn from(color: PaletteColor) -> (u8, u8, u8) {
+        (color.red.0, color.green.0, color.blue.0)
+    }
+}
+
+/// Represents a 256 color vga palette, stored in the same layout
+/// `ColorPaletteRegisters::load_palette` expects.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::colors::{Palette, PaletteColor};
+/// use vga::vga::VGA;
+///
+/// let mut palette = Palette::default();
+/// palette.set(0, PaletteColor::new(0x00, 0x00, 0x2A));
+///
+/// VGA.lock()
+///     .color_palette_registers
+///     .load_palette_colors(&palette);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette([u8; PALETTE_SIZE]);
+
+impl Palette {
+    /// Returns a new `Palette` from `bytes`, with every 3 bytes representing a color.
+    pub const fn from_bytes(bytes: [u8; PALETTE_SIZE]) -> Palette {
+        Palette(bytes)
+    }
+
+    /// Returns the `PaletteColor` at `index`.
+    pub fn get(&self, index: u8) -> PaletteColor {
+        let offset = usize::from(index) * 3;
+        PaletteColor::new(self.0[offset], self.0[offset + 1], self.0[offset + 2])
+    }
+
+    /// Sets the color at `index` to the given `color`.
+    pub fn set(&mut self, index: u8, color: PaletteColor) {
+        let offset = usize::from(index) * 3;
+        self.0[offset] = color.red.0;
+        self.0[offset + 1] = color.green.0;
+        self.0[offset + 2] = color.blue.0;
+    }
+
+    /// Returns the raw bytes of the `Palette`, with every 3 bytes representing a color.
+    pub fn as_bytes(&self) -> &[u8; PALETTE_SIZE] {
+        &self.0
+    }
+
+    /// Returns a copy of the raw bytes of the `Palette`.
+    pub fn to_bytes(&self) -> [u8; PALETTE_SIZE] {
+        self.0
+    }
+}
+
+impl Default for Palette {
+    /// Returns the `DEFAULT_PALETTE`.
+    fn default() -> Palette {
+        Palette(DEFAULT_PALETTE)
     }
 }
 
@@ -139,4 +539,156 @@ mod test {
         color.set_background(Color16::DarkGrey);
         assert_eq!(color.0 >> 4, Color16::DarkGrey as u8);
     }
+
+    #[test]
+    fn test_set_foreground_and_background() {
+        let mut color = TextModeColor::new(Color16::Yellow, Color16::Black);
+        color.set_background(Color16::DarkGrey);
+        color.set_foreground(Color16::Red);
+        assert_eq!(color.0 & 0x0F, Color16::Red as u8);
+        assert_eq!(color.0 >> 4, Color16::DarkGrey as u8);
+    }
+
+    #[test]
+    fn test_text_mode_color_default() {
+        let color = TextModeColor::default();
+        assert_eq!(color.foreground(), Color16::LightGrey);
+        assert_eq!(color.background(), Color16::Black);
+        assert_eq!(color, TextModeColor::LIGHT_GREY_ON_BLACK);
+    }
+
+    #[test]
+    fn test_set_blink() {
+        let mut color = TextModeColor::new(Color16::Yellow, Color16::Blue);
+        color.set_blink(true);
+        assert!(color.is_blinking());
+        assert_eq!(color.background(), Color16::LightBlue);
+        assert_eq!(color.foreground(), Color16::Yellow);
+        color.set_blink(false);
+        assert!(!color.is_blinking());
+        assert_eq!(color.background(), Color16::Blue);
+    }
+
+    #[test]
+    fn test_color16_all() {
+        assert_eq!(Color16::all().count(), 16);
+        for (value, color) in Color16::all().enumerate() {
+            assert_eq!(u8::from(color), value as u8);
+        }
+    }
+
+    #[test]
+    fn test_color16_try_from() {
+        for value in 0x0..=0xF {
+            let color = Color16::try_from(value).unwrap();
+            assert_eq!(u8::from(color), value);
+        }
+        assert!(Color16::try_from(0x10).is_err());
+        assert!(Color16::try_from(0xFF).is_err());
+    }
+
+    #[test]
+    fn test_palette_get_and_set() {
+        let mut palette = Palette::default();
+        assert_eq!(palette.get(1), PaletteColor::new(0x00, 0x00, 0x2A));
+
+        palette.set(255, PaletteColor::new(0x01, 0x02, 0x03));
+        assert_eq!(palette.get(255), PaletteColor::new(0x01, 0x02, 0x03));
+        assert_eq!(palette.as_bytes()[PALETTE_SIZE - 3..], [0x01, 0x02, 0x03]);
+        assert_eq!(Palette::from_bytes(palette.to_bytes()), palette);
+    }
+
+    #[test]
+    fn test_rgb888_dac_conversion() {
+        assert_eq!(rgb888_to_dac((0x00, 0x80, 0xFF)), (0x00, 0x20, 0x3F));
+        assert_eq!(dac_to_rgb888((0x00, 0x20, 0x3F)), (0x00, 0x82, 0xFF));
+        for component in 0..=0x3F {
+            let color = (component, component, component);
+            assert_eq!(rgb888_to_dac(dac_to_rgb888(color)), color);
+        }
+
+        let palette = palette_from_rgb888(&[(0xFF, 0x00, 0x00), (0x00, 0x00, 0xAA)]);
+        assert_eq!(palette[..6], [0x3F, 0x00, 0x00, 0x00, 0x00, 0x2A]);
+        assert!(palette[6..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_gamma_2_2() {
+        assert_eq!(GAMMA_2_2[0], 0x00);
+        assert_eq!(GAMMA_2_2[0x3F], 0x3F);
+        for (i, value) in GAMMA_2_2.iter().enumerate().skip(1) {
+            assert!(*value >= GAMMA_2_2[i - 1]);
+            assert!(usize::from(*value) >= i);
+        }
+    }
+
+    #[test]
+    fn test_grayscale() {
+        let mut palette = [0; PALETTE_SIZE];
+        palette[3..9].copy_from_slice(&[0x3F, 0x3F, 0x3F, 0x00, 0x00, 0x2A]);
+        let palette = grayscale(&palette);
+        assert_eq!(palette[..3], [0x00, 0x00, 0x00]);
+        assert_eq!(palette[3..6], [0x3F, 0x3F, 0x3F]);
+        assert_eq!(palette[6..9], [0x05, 0x05, 0x05]);
+
+        for gray in grayscale_palette().chunks_exact(3) {
+            assert_eq!(gray[0], gray[1]);
+            assert_eq!(gray[1], gray[2]);
+        }
+    }
+
+    #[test]
+    fn test_color16_to_rgb() {
+        assert_eq!(Color16::Black.to_rgb(), (0x00, 0x00, 0x00));
+        assert_eq!(Color16::Blue.to_rgb(), (0x00, 0x00, 0xAA));
+        assert_eq!(Color16::Brown.to_rgb(), (0xAA, 0x55, 0x00));
+        assert_eq!(Color16::LightGrey.to_rgb(), (0xAA, 0xAA, 0xAA));
+        assert_eq!(Color16::DarkGrey.to_rgb(), (0x55, 0x55, 0x55));
+        assert_eq!(Color16::Yellow.to_rgb(), (0xFF, 0xFF, 0x55));
+        assert_eq!(Color16::White.to_rgb(), (0xFF, 0xFF, 0xFF));
+
+        for (i, color) in Color16::all().enumerate() {
+            assert_eq!(color.to_rgb(), COLOR16_RGB[i]);
+            assert!(Color16::all()
+                .skip(i + 1)
+                .all(|other| other.to_rgb() != color.to_rgb()));
+        }
+    }
+
+    #[test]
+    fn test_palette_color() {
+        assert_eq!(DacComponent::new(0x40).value(), 0x00);
+        assert_eq!(DacComponent::new(0x7F).value(), 0x3F);
+        assert_eq!(DacComponent::new_clamped(0x40), DacComponent::MAX);
+        assert_eq!(DacComponent::new_clamped(0x2A).value(), 0x2A);
+
+        let color = PaletteColor::new(0x10, 0x3F, 0xFF);
+        assert_eq!(<(u8, u8, u8)>::from(color), (0x10, 0x3F, 0x3F));
+
+        let color = PaletteColor::new_clamped(0x10, 0x3F, 0xFF);
+        assert_eq!(<(u8, u8, u8)>::from(color), (0x10, 0x3F, 0x3F));
+        let color = PaletteColor::new_clamped(0x40, 0x7F, 0x00);
+        assert_eq!(<(u8, u8, u8)>::from(color), (0x3F, 0x3F, 0x00));
+
+        let color = PaletteColor::from_rgb888(0xFF, 0x80, 0x00);
+        assert_eq!(color, PaletteColor::new(0x3F, 0x20, 0x00));
+        assert_eq!(color.to_rgb888(), (0xFF, 0x82, 0x00));
+    }
+
+    #[test]
+    fn test_nearest_color16() {
+        for (value, &(red, green, blue)) in COLOR16_RGB.iter().enumerate() {
+            assert_eq!(u8::from(nearest_color16(red, green, blue)), value as u8);
+        }
+        assert_eq!(nearest_color16(0xB0, 0x60, 0x10), Color16::Brown);
+        assert_eq!(nearest_color16(0x10, 0x10, 0x10), Color16::Black);
+        assert_eq!(nearest_color16(0xF0, 0xF0, 0xF0), Color16::White);
+    }
+
+    #[test]
+    fn test_foreground_and_background() {
+        let color = TextModeColor::new(Color16::Pink, Color16::Cyan);
+        assert_eq!(color.foreground(), Color16::Pink);
+        assert_eq!(color.background(), Color16::Cyan);
+    }
 }
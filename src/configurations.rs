@@ -1,7 +1,10 @@
 //! Common video configurations used in vga programming.
 
-use super::registers::{
-    AttributeControllerIndex, CrtcControllerIndex, GraphicsControllerIndex, SequencerIndex,
+use super::{
+    registers::{
+        AttributeControllerIndex, CrtcControllerIndex, GraphicsControllerIndex, SequencerIndex,
+    },
+    vga::FrameBuffer,
 };
 
 /// Represents a set of vga registers for a given mode.
@@ -19,6 +22,23 @@ pub struct VgaConfiguration {
     pub attribute_controller_registers: &'static [(AttributeControllerIndex, u8)],
 }
 
+impl VgaConfiguration {
+    /// Returns the `FrameBuffer` selected by the memory map bits of the
+    /// graphics controller's miscellaneous register, or `None` if the
+    /// configuration doesn't set that register.
+    pub fn frame_buffer(&self) -> Option<FrameBuffer> {
+        let (_, miscellaneous) = self
+            .graphics_controller_registers
+            .iter()
+            .find(|(index, _)| matches!(index, GraphicsControllerIndex::Miscellaneous))?;
+        match (miscellaneous >> 2) & 0x3 {
+            0x2 => Some(FrameBuffer::MdaMode),
+            0x3 => Some(FrameBuffer::CgaMode),
+            _ => Some(FrameBuffer::GraphicsMode),
+        }
+    }
+}
+
 /// Register values for Vga mode 40x25 Text.
 pub const MODE_40X25_CONFIGURATION: VgaConfiguration = VgaConfiguration {
     // Configuration values acquired from https://www.singlix.com/trdos/archive/vga/Graphics%20in%20pmode.pdf
@@ -241,6 +261,80 @@ pub const MODE_80X25_CONFIGURATION: VgaConfiguration = VgaConfiguration {
     ],
 };
 
+/// Register values for Vga mode 80x50 Text.
+pub const MODE_80X50_CONFIGURATION: VgaConfiguration = VgaConfiguration {
+    // The 80x25 configuration with 8 scan lines per character row.
+    miscellaneous_output: 0x67,
+    sequencer_registers: &[
+        (SequencerIndex::SequencerReset, 0x03),
+        (SequencerIndex::ClockingMode, 0x00),
+        (SequencerIndex::PlaneMask, 0x03),
+        (SequencerIndex::CharacterFont, 0x00),
+        (SequencerIndex::MemoryMode, 0x02),
+    ],
+    crtc_controller_registers: &[
+        (CrtcControllerIndex::HorizontalTotal, 0x5F),
+        (CrtcControllerIndex::HorizontalDisplayEnableEnd, 0x4F),
+        (CrtcControllerIndex::HorizontalBlankingStart, 0x50),
+        (CrtcControllerIndex::HorizontalBlankingEnd, 0x82),
+        (CrtcControllerIndex::HorizontalSyncStart, 0x55),
+        (CrtcControllerIndex::HorizontalSyncEnd, 0x81),
+        (CrtcControllerIndex::VeritcalTotal, 0xBF),
+        (CrtcControllerIndex::Overflow, 0x1F),
+        (CrtcControllerIndex::PresetRowScan, 0x00),
+        (CrtcControllerIndex::MaximumScanLine, 0x47),
+        (CrtcControllerIndex::TextCursorStart, 0x06),
+        (CrtcControllerIndex::TextCursorEnd, 0x07),
+        (CrtcControllerIndex::StartAddressHigh, 0x00),
+        (CrtcControllerIndex::StartAddressLow, 0x00),
+        (CrtcControllerIndex::TextCursorLocationHigh, 0x00),
+        (CrtcControllerIndex::TextCursorLocationLow, 0x50),
+        (CrtcControllerIndex::VerticalSyncStart, 0x9C),
+        (CrtcControllerIndex::VerticalSyncEnd, 0x0E),
+        (CrtcControllerIndex::VerticalDisplayEnableEnd, 0x8F),
+        (CrtcControllerIndex::Offset, 0x28),
+        (CrtcControllerIndex::UnderlineLocation, 0x1F),
+        (CrtcControllerIndex::VerticalBlankingStart, 0x96),
+        (CrtcControllerIndex::VerticalBlankingEnd, 0xB9),
+        (CrtcControllerIndex::ModeControl, 0xA3),
+        (CrtcControllerIndex::LineCompare, 0xFF),
+    ],
+    graphics_controller_registers: &[
+        (GraphicsControllerIndex::SetReset, 0x00),
+        (GraphicsControllerIndex::EnableSetReset, 0x00),
+        (GraphicsControllerIndex::ColorCompare, 0x00),
+        (GraphicsControllerIndex::DataRotate, 0x00),
+        (GraphicsControllerIndex::ReadPlaneSelect, 0x00),
+        (GraphicsControllerIndex::GraphicsMode, 0x10),
+        (GraphicsControllerIndex::Miscellaneous, 0x0E),
+        (GraphicsControllerIndex::ColorDontCare, 0x00),
+        (GraphicsControllerIndex::BitMask, 0xFF),
+    ],
+    attribute_controller_registers: &[
+        (AttributeControllerIndex::PaletteRegister0, 0x00),
+        (AttributeControllerIndex::PaletteRegister1, 0x01),
+        (AttributeControllerIndex::PaletteRegister2, 0x02),
+        (AttributeControllerIndex::PaletteRegister3, 0x03),
+        (AttributeControllerIndex::PaletteRegister4, 0x04),
+        (AttributeControllerIndex::PaletteRegister5, 0x05),
+        (AttributeControllerIndex::PaletteRegister6, 0x14),
+        (AttributeControllerIndex::PaletteRegister7, 0x07),
+        (AttributeControllerIndex::PaletteRegister8, 0x38),
+        (AttributeControllerIndex::PaletteRegister9, 0x39),
+        (AttributeControllerIndex::PaletteRegisterA, 0x3A),
+        (AttributeControllerIndex::PaletteRegisterB, 0x3B),
+        (AttributeControllerIndex::PaletteRegisterC, 0x3C),
+        (AttributeControllerIndex::PaletteRegisterD, 0x3D),
+        (AttributeControllerIndex::PaletteRegisterE, 0x3E),
+        (AttributeControllerIndex::PaletteRegisterF, 0x3F),
+        (AttributeControllerIndex::ModeControl, 0x0C),
+        (AttributeControllerIndex::OverscanColor, 0x00),
+        (AttributeControllerIndex::MemoryPlaneEnable, 0x0F),
+        (AttributeControllerIndex::HorizontalPixelPanning, 0x08),
+        (AttributeControllerIndex::ColorSelect, 0x00),
+    ],
+};
+
 /// Register values for Vga mode 640x480x16 Graphics.
 pub const MODE_640X480X16_CONFIGURATION: VgaConfiguration = VgaConfiguration {
     // Configuration values acquired from https://www.singlix.com/trdos/archive/vga/Graphics%20in%20pmode.pdf
@@ -316,6 +410,234 @@ pub const MODE_640X480X16_CONFIGURATION: VgaConfiguration = VgaConfiguration {
     ],
 };
 
+/// Register values for Vga mode 640x350x16 Graphics.
+pub const MODE_640X350X16_CONFIGURATION: VgaConfiguration = VgaConfiguration {
+    // Configuration values acquired from the parameter table of the IBM VGA bios
+    // for mode 10h, with the same attribute palette as `MODE_640X480X16_CONFIGURATION`
+    // so the modes share `DEFAULT_PALETTE`.
+    miscellaneous_output: 0xA3,
+    sequencer_registers: &[
+        (SequencerIndex::SequencerReset, 0x03),
+        (SequencerIndex::ClockingMode, 0x01),
+        (SequencerIndex::PlaneMask, 0x0F),
+        (SequencerIndex::CharacterFont, 0x00),
+        (SequencerIndex::MemoryMode, 0x06),
+    ],
+    crtc_controller_registers: &[
+        (CrtcControllerIndex::HorizontalTotal, 0x5F),
+        (CrtcControllerIndex::HorizontalDisplayEnableEnd, 0x4F),
+        (CrtcControllerIndex::HorizontalBlankingStart, 0x50),
+        (CrtcControllerIndex::HorizontalBlankingEnd, 0x82),
+        (CrtcControllerIndex::HorizontalSyncStart, 0x54),
+        (CrtcControllerIndex::HorizontalSyncEnd, 0x80),
+        (CrtcControllerIndex::VeritcalTotal, 0xBF),
+        (CrtcControllerIndex::Overflow, 0x1F),
+        (CrtcControllerIndex::PresetRowScan, 0x00),
+        (CrtcControllerIndex::MaximumScanLine, 0x40),
+        (CrtcControllerIndex::TextCursorStart, 0x00),
+        (CrtcControllerIndex::TextCursorEnd, 0x00),
+        (CrtcControllerIndex::StartAddressHigh, 0x00),
+        (CrtcControllerIndex::StartAddressLow, 0x00),
+        (CrtcControllerIndex::TextCursorLocationHigh, 0x00),
+        (CrtcControllerIndex::TextCursorLocationLow, 0x00),
+        (CrtcControllerIndex::VerticalSyncStart, 0x83),
+        (CrtcControllerIndex::VerticalSyncEnd, 0x85),
+        (CrtcControllerIndex::VerticalDisplayEnableEnd, 0x5D),
+        (CrtcControllerIndex::Offset, 0x28),
+        (CrtcControllerIndex::UnderlineLocation, 0x0F),
+        (CrtcControllerIndex::VerticalBlankingStart, 0x63),
+        (CrtcControllerIndex::VerticalBlankingEnd, 0xBA),
+        (CrtcControllerIndex::ModeControl, 0xE3),
+        (CrtcControllerIndex::LineCompare, 0xFF),
+    ],
+    graphics_controller_registers: &[
+        (GraphicsControllerIndex::SetReset, 0x00),
+        (GraphicsControllerIndex::EnableSetReset, 0x00),
+        (GraphicsControllerIndex::ColorCompare, 0x00),
+        (GraphicsControllerIndex::DataRotate, 0x00),
+        (GraphicsControllerIndex::ReadPlaneSelect, 0x00),
+        (GraphicsControllerIndex::GraphicsMode, 0x00),
+        (GraphicsControllerIndex::Miscellaneous, 0x05),
+        (GraphicsControllerIndex::ColorDontCare, 0x0F),
+        (GraphicsControllerIndex::BitMask, 0xFF),
+    ],
+    attribute_controller_registers: &[
+        (AttributeControllerIndex::PaletteRegister0, 0x00),
+        (AttributeControllerIndex::PaletteRegister1, 0x01),
+        (AttributeControllerIndex::PaletteRegister2, 0x02),
+        (AttributeControllerIndex::PaletteRegister3, 0x03),
+        (AttributeControllerIndex::PaletteRegister4, 0x04),
+        (AttributeControllerIndex::PaletteRegister5, 0x05),
+        (AttributeControllerIndex::PaletteRegister6, 0x14),
+        (AttributeControllerIndex::PaletteRegister7, 0x07),
+        (AttributeControllerIndex::PaletteRegister8, 0x38),
+        (AttributeControllerIndex::PaletteRegister9, 0x39),
+        (AttributeControllerIndex::PaletteRegisterA, 0x3A),
+        (AttributeControllerIndex::PaletteRegisterB, 0x3B),
+        (AttributeControllerIndex::PaletteRegisterC, 0x3C),
+        (AttributeControllerIndex::PaletteRegisterD, 0x3D),
+        (AttributeControllerIndex::PaletteRegisterE, 0x3E),
+        (AttributeControllerIndex::PaletteRegisterF, 0x3F),
+        (AttributeControllerIndex::ModeControl, 0x01),
+        (AttributeControllerIndex::OverscanColor, 0x00),
+        (AttributeControllerIndex::MemoryPlaneEnable, 0x0F),
+        (AttributeControllerIndex::HorizontalPixelPanning, 0x00),
+        (AttributeControllerIndex::ColorSelect, 0x00),
+    ],
+};
+
+/// Register values for Vga mode 320x200x16 Graphics.
+pub const MODE_320X200X16_CONFIGURATION: VgaConfiguration = VgaConfiguration {
+    // Configuration values acquired from the parameter table of the IBM VGA bios
+    // for mode 0Dh, with the same attribute palette as `MODE_640X480X16_CONFIGURATION`
+    // so the modes share `DEFAULT_PALETTE`.
+    miscellaneous_output: 0x63,
+    sequencer_registers: &[
+        (SequencerIndex::SequencerReset, 0x03),
+        (SequencerIndex::ClockingMode, 0x09),
+        (SequencerIndex::PlaneMask, 0x0F),
+        (SequencerIndex::CharacterFont, 0x00),
+        (SequencerIndex::MemoryMode, 0x06),
+    ],
+    crtc_controller_registers: &[
+        (CrtcControllerIndex::HorizontalTotal, 0x2D),
+        (CrtcControllerIndex::HorizontalDisplayEnableEnd, 0x27),
+        (CrtcControllerIndex::HorizontalBlankingStart, 0x28),
+        (CrtcControllerIndex::HorizontalBlankingEnd, 0x90),
+        (CrtcControllerIndex::HorizontalSyncStart, 0x2B),
+        (CrtcControllerIndex::HorizontalSyncEnd, 0x80),
+        (CrtcControllerIndex::VeritcalTotal, 0xBF),
+        (CrtcControllerIndex::Overflow, 0x1F),
+        (CrtcControllerIndex::PresetRowScan, 0x00),
+        (CrtcControllerIndex::MaximumScanLine, 0xC0),
+        (CrtcControllerIndex::TextCursorStart, 0x00),
+        (CrtcControllerIndex::TextCursorEnd, 0x00),
+        (CrtcControllerIndex::StartAddressHigh, 0x00),
+        (CrtcControllerIndex::StartAddressLow, 0x00),
+        (CrtcControllerIndex::TextCursorLocationHigh, 0x00),
+        (CrtcControllerIndex::TextCursorLocationLow, 0x00),
+        (CrtcControllerIndex::VerticalSyncStart, 0x9C),
+        (CrtcControllerIndex::VerticalSyncEnd, 0x8E),
+        (CrtcControllerIndex::VerticalDisplayEnableEnd, 0x8F),
+        (CrtcControllerIndex::Offset, 0x14),
+        (CrtcControllerIndex::UnderlineLocation, 0x00),
+        (CrtcControllerIndex::VerticalBlankingStart, 0x96),
+        (CrtcControllerIndex::VerticalBlankingEnd, 0xB9),
+        (CrtcControllerIndex::ModeControl, 0xE3),
+        (CrtcControllerIndex::LineCompare, 0xFF),
+    ],
+    graphics_controller_registers: &[
+        (GraphicsControllerIndex::SetReset, 0x00),
+        (GraphicsControllerIndex::EnableSetReset, 0x00),
+        (GraphicsControllerIndex::ColorCompare, 0x00),
+        (GraphicsControllerIndex::DataRotate, 0x00),
+        (GraphicsControllerIndex::ReadPlaneSelect, 0x00),
+        (GraphicsControllerIndex::GraphicsMode, 0x00),
+        (GraphicsControllerIndex::Miscellaneous, 0x05),
+        (GraphicsControllerIndex::ColorDontCare, 0x0F),
+        (GraphicsControllerIndex::BitMask, 0xFF),
+    ],
+    attribute_controller_registers: &[
+        (AttributeControllerIndex::PaletteRegister0, 0x00),
+        (AttributeControllerIndex::PaletteRegister1, 0x01),
+        (AttributeControllerIndex::PaletteRegister2, 0x02),
+        (AttributeControllerIndex::PaletteRegister3, 0x03),
+        (AttributeControllerIndex::PaletteRegister4, 0x04),
+        (AttributeControllerIndex::PaletteRegister5, 0x05),
+        (AttributeControllerIndex::PaletteRegister6, 0x14),
+        (AttributeControllerIndex::PaletteRegister7, 0x07),
+        (AttributeControllerIndex::PaletteRegister8, 0x38),
+        (AttributeControllerIndex::PaletteRegister9, 0x39),
+        (AttributeControllerIndex::PaletteRegisterA, 0x3A),
+        (AttributeControllerIndex::PaletteRegisterB, 0x3B),
+        (AttributeControllerIndex::PaletteRegisterC, 0x3C),
+        (AttributeControllerIndex::PaletteRegisterD, 0x3D),
+        (AttributeControllerIndex::PaletteRegisterE, 0x3E),
+        (AttributeControllerIndex::PaletteRegisterF, 0x3F),
+        (AttributeControllerIndex::ModeControl, 0x01),
+        (AttributeControllerIndex::OverscanColor, 0x00),
+        (AttributeControllerIndex::MemoryPlaneEnable, 0x0F),
+        (AttributeControllerIndex::HorizontalPixelPanning, 0x00),
+        (AttributeControllerIndex::ColorSelect, 0x00),
+    ],
+};
+
+/// Register values for Vga mode 320x200x4 Graphics.
+pub const MODE_320X200X4_CONFIGURATION: VgaConfiguration = VgaConfiguration {
+    // Configuration values acquired from https://www.singlix.com/trdos/archive/vga/Graphics%20in%20pmode.pdf
+    miscellaneous_output: 0x63,
+    sequencer_registers: &[
+        (SequencerIndex::SequencerReset, 0x03),
+        (SequencerIndex::ClockingMode, 0x09),
+        (SequencerIndex::PlaneMask, 0x03),
+        (SequencerIndex::CharacterFont, 0x00),
+        (SequencerIndex::MemoryMode, 0x02),
+    ],
+    crtc_controller_registers: &[
+        (CrtcControllerIndex::HorizontalTotal, 0x2D),
+        (CrtcControllerIndex::HorizontalDisplayEnableEnd, 0x27),
+        (CrtcControllerIndex::HorizontalBlankingStart, 0x28),
+        (CrtcControllerIndex::HorizontalBlankingEnd, 0x90),
+        (CrtcControllerIndex::HorizontalSyncStart, 0x2B),
+        (CrtcControllerIndex::HorizontalSyncEnd, 0x80),
+        (CrtcControllerIndex::VeritcalTotal, 0xBF),
+        (CrtcControllerIndex::Overflow, 0x1F),
+        (CrtcControllerIndex::PresetRowScan, 0x00),
+        (CrtcControllerIndex::MaximumScanLine, 0xC1),
+        (CrtcControllerIndex::TextCursorStart, 0x00),
+        (CrtcControllerIndex::TextCursorEnd, 0x00),
+        (CrtcControllerIndex::StartAddressHigh, 0x00),
+        (CrtcControllerIndex::StartAddressLow, 0x00),
+        (CrtcControllerIndex::TextCursorLocationHigh, 0x00),
+        (CrtcControllerIndex::TextCursorLocationLow, 0x00),
+        (CrtcControllerIndex::VerticalSyncStart, 0x9C),
+        (CrtcControllerIndex::VerticalSyncEnd, 0x8E),
+        (CrtcControllerIndex::VerticalDisplayEnableEnd, 0x8F),
+        (CrtcControllerIndex::Offset, 0x14),
+        (CrtcControllerIndex::UnderlineLocation, 0x00),
+        (CrtcControllerIndex::VerticalBlankingStart, 0x96),
+        (CrtcControllerIndex::VerticalBlankingEnd, 0xB9),
+        (CrtcControllerIndex::ModeControl, 0xA2),
+        (CrtcControllerIndex::LineCompare, 0xFF),
+    ],
+    graphics_controller_registers: &[
+        (GraphicsControllerIndex::SetReset, 0x00),
+        (GraphicsControllerIndex::EnableSetReset, 0x00),
+        (GraphicsControllerIndex::ColorCompare, 0x00),
+        (GraphicsControllerIndex::DataRotate, 0x00),
+        (GraphicsControllerIndex::ReadPlaneSelect, 0x00),
+        (GraphicsControllerIndex::GraphicsMode, 0x30),
+        (GraphicsControllerIndex::Miscellaneous, 0x0F),
+        (GraphicsControllerIndex::ColorDontCare, 0x00),
+        (GraphicsControllerIndex::BitMask, 0xFF),
+    ],
+    attribute_controller_registers: &[
+        // Colors 1 to 3 map to light cyan, light magenta and white in the
+        // `DEFAULT_PALETTE`, matching the high intensity cga palette 1.
+        (AttributeControllerIndex::PaletteRegister0, 0x00),
+        (AttributeControllerIndex::PaletteRegister1, 0x3B),
+        (AttributeControllerIndex::PaletteRegister2, 0x3D),
+        (AttributeControllerIndex::PaletteRegister3, 0x3F),
+        (AttributeControllerIndex::PaletteRegister4, 0x04),
+        (AttributeControllerIndex::PaletteRegister5, 0x05),
+        (AttributeControllerIndex::PaletteRegister6, 0x14),
+        (AttributeControllerIndex::PaletteRegister7, 0x07),
+        (AttributeControllerIndex::PaletteRegister8, 0x38),
+        (AttributeControllerIndex::PaletteRegister9, 0x39),
+        (AttributeControllerIndex::PaletteRegisterA, 0x3A),
+        (AttributeControllerIndex::PaletteRegisterB, 0x3B),
+        (AttributeControllerIndex::PaletteRegisterC, 0x3C),
+        (AttributeControllerIndex::PaletteRegisterD, 0x3D),
+        (AttributeControllerIndex::PaletteRegisterE, 0x3E),
+        (AttributeControllerIndex::PaletteRegisterF, 0x3F),
+        (AttributeControllerIndex::ModeControl, 0x01),
+        (AttributeControllerIndex::OverscanColor, 0x00),
+        (AttributeControllerIndex::MemoryPlaneEnable, 0x03),
+        (AttributeControllerIndex::HorizontalPixelPanning, 0x00),
+        (AttributeControllerIndex::ColorSelect, 0x00),
+    ],
+};
+
 /// Register values for Vga mode 320x200x256 Graphics.
 pub const MODE_320X200X256_CONFIGURATION: VgaConfiguration = VgaConfiguration {
     // Configuration values acquired from https://www.singlix.com/trdos/archive/vga/Graphics%20in%20pmode.pdf
@@ -463,3 +785,29 @@ pub const MODE_320X240X256_CONFIGURATION: VgaConfiguration = VgaConfiguration {
         (AttributeControllerIndex::ColorSelect, 0x00),
     ],
 };
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_configuration_frame_buffer() {
+        let frame_buffer =
+            |configuration: &VgaConfiguration| configuration.frame_buffer().map(u32::from);
+        assert_eq!(frame_buffer(&MODE_80X25_CONFIGURATION), Some(0xB8000));
+        assert_eq!(frame_buffer(&MODE_320X200X4_CONFIGURATION), Some(0xB8000));
+        assert_eq!(frame_buffer(&MODE_640X480X16_CONFIGURATION), Some(0xA0000));
+        assert_eq!(frame_buffer(&MODE_640X350X16_CONFIGURATION), Some(0xA0000));
+        assert_eq!(frame_buffer(&MODE_320X200X16_CONFIGURATION), Some(0xA0000));
+        assert_eq!(frame_buffer(&MODE_320X240X256_CONFIGURATION), Some(0xA0000));
+
+        let empty = VgaConfiguration {
+            miscellaneous_output: 0x67,
+            sequencer_registers: &[],
+            crtc_controller_registers: &[],
+            graphics_controller_registers: &[],
+            attribute_controller_registers: &[],
+        };
+        assert_eq!(frame_buffer(&empty), None);
+    }
+}
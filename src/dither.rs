@@ -0,0 +1,131 @@
+//! Ordered dithering with Bayer threshold matrices.
+//!
+//! Ordered dithering decides each pixel on its own from its position, so unlike
+//! error diffusion it doesn't need a buffer for the rows still to be drawn.
+
+use crate::colors::{nearest_color16, Color16};
+
+/// The 4x4 Bayer matrix, holding the thresholds `0..16` in the order the
+/// pixels of each 4x4 block switch on.
+pub const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// The 8x8 Bayer matrix, holding the thresholds `0..64` in the order the
+/// pixels of each 8x8 block switch on.
+pub const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// The distance between the levels of each component in `COLOR16_RGB`.
+const COLOR16_STEP: i16 = 0x55;
+
+/// Returns `true` if the pixel at `(x, y)` is on for an intensity of `level`
+/// out of 64, as decided by `BAYER_8X8`. Exactly `level` pixels of every
+/// 8x8 block are on.
+pub fn dither_threshold(x: usize, y: usize, level: u8) -> bool {
+    BAYER_8X8[y % 8][x % 8] < level
+}
+
+/// Returns the `Color16` for the pixel at `(x, y)` of a 24 bit image, nudging
+/// each component by up to half the distance between the levels of the
+/// 16 colors according to `BAYER_8X8` before picking the nearest color.
+pub fn dither_color16(x: usize, y: usize, color: (u8, u8, u8)) -> Color16 {
+    // Centers the thresholds on 0, giving an offset in `-0.5..0.5` of a step.
+    let offset = (i16::from(BAYER_8X8[y % 8][x % 8]) * 2 + 1 - 64) * COLOR16_STEP / 128;
+    let nudge = |component: u8| (i16::from(component) + offset).clamp(0, 255) as u8;
+    nearest_color16(nudge(color.0), nudge(color.1), nudge(color.2))
+}
+
+/// How a 24 bit color is turned into one of the 16 colors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorMatching {
+    /// Every pixel takes the nearest color, as picked by `nearest_color16`.
+    #[default]
+    Nearest,
+    /// Pixels are dithered between the nearest colors by `dither_color16`,
+    /// which gives smoother gradients at the cost of a fine pattern.
+    Ordered,
+}
+
+impl ColorMatching {
+    /// Returns the `Color16` for the pixel at `(x, y)` of a 24 bit image.
+    pub fn color16(self, x: usize, y: usize, color: (u8, u8, u8)) -> Color16 {
+        match self {
+            ColorMatching::Nearest => nearest_color16(color.0, color.1, color.2),
+            ColorMatching::Ordered => dither_color16(x, y, color),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bayer_matrices() {
+        let mut seen = [false; 64];
+        for &threshold in BAYER_4X4.iter().flatten() {
+            assert!(!seen[usize::from(threshold)]);
+            seen[usize::from(threshold)] = true;
+        }
+        assert!(seen[..16].iter().all(|&seen| seen));
+
+        let mut seen = [false; 64];
+        for &threshold in BAYER_8X8.iter().flatten() {
+            assert!(!seen[usize::from(threshold)]);
+            seen[usize::from(threshold)] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn test_dither_threshold() {
+        for level in 0..=64 {
+            let count = (0..64)
+                .filter(|i| dither_threshold(i % 8 + 16, i / 8 + 8, level))
+                .count();
+            assert_eq!(count, usize::from(level));
+        }
+    }
+
+    #[test]
+    fn test_color_matching() {
+        let grey = (0x2A, 0x2A, 0x2A);
+        assert!(
+            (0..64).all(|i| ColorMatching::Nearest.color16(i % 8, i / 8, grey)
+                == nearest_color16(0x2A, 0x2A, 0x2A))
+        );
+        assert!(
+            (0..64).all(|i| ColorMatching::Ordered.color16(i % 8, i / 8, grey)
+                == dither_color16(i % 8, i / 8, grey))
+        );
+    }
+
+    #[test]
+    fn test_dither_color16() {
+        // Colors of the palette stay the same everywhere.
+        for x in 0..8 {
+            for y in 0..8 {
+                assert_eq!(dither_color16(x, y, (0, 0, 0)), Color16::Black);
+                assert_eq!(dither_color16(x, y, (0xFF, 0xFF, 0xFF)), Color16::White);
+                assert_eq!(dither_color16(x, y, (0xAA, 0, 0)), Color16::Red);
+            }
+        }
+
+        // A grey between black and dark grey mixes the two.
+        let grey = (0x2A, 0x2A, 0x2A);
+        let dark_grey = (0..64)
+            .filter(|i| dither_color16(i % 8, i / 8, grey) == Color16::DarkGrey)
+            .count();
+        let black = (0..64)
+            .filter(|i| dither_color16(i % 8, i / 8, grey) == Color16::Black)
+            .count();
+        assert!(dark_grey > 16 && black > 16 && dark_grey + black == 64);
+    }
+}
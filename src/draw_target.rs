@@ -0,0 +1,78 @@
+//! Support for drawing with [`embedded-graphics`](https://docs.rs/embedded-graphics),
+//! enabled with the `embedded-graphics` feature.
+use crate::{
+    colors::{nearest_color16, Color16},
+    writers::{GraphicsWriter, PlanarGraphics},
+};
+use core::convert::Infallible;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{raw::RawU4, Gray4, GrayColor, PixelColor, Rgb888, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+
+impl PixelColor for Color16 {
+    type Raw = RawU4;
+}
+
+impl From<Rgb888> for Color16 {
+    fn from(color: Rgb888) -> Color16 {
+        nearest_color16(color.r(), color.g(), color.b())
+    }
+}
+
+impl From<Color16> for Rgb888 {
+    fn from(color: Color16) -> Rgb888 {
+        let (red, green, blue) = color.to_rgb();
+        Rgb888::new(red, green, blue)
+    }
+}
+
+impl From<Gray4> for Color16 {
+    fn from(color: Gray4) -> Color16 {
+        // Scale the 4 bit luma to 8 bits, i.e. 0xF becomes 0xFF.
+        let luma = color.luma() * 0x11;
+        nearest_color16(luma, luma, luma)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> OriginDimensions for PlanarGraphics<WIDTH, HEIGHT> {
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> DrawTarget for PlanarGraphics<WIDTH, HEIGHT> {
+    type Color = Color16;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.draw_pixels(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| ((point.x as isize, point.y as isize), color)),
+        );
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        self.fill_rect(
+            (area.top_left.x, area.top_left.y),
+            area.size.width as usize,
+            area.size.height as usize,
+            color,
+        );
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.clear_screen(color);
+        Ok(())
+    }
+}
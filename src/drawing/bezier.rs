@@ -0,0 +1,191 @@
+use super::Coordinates;
+
+/// The most steps a curve is split into, which bounds the work done for
+/// control points far outside of the screen.
+const MAX_STEPS: usize = 1024;
+
+/// Iterates over `steps + 1` evenly spaced points of a quadratic or cubic
+/// Bézier curve, from its first to its last control point.
+///
+/// Each coordinate is a polynomial in the step `i`, scaled by `steps` to the
+/// power of the curve's degree to keep it an integer. The polynomial is walked
+/// with forward differences, which are exact in integers, so the curve takes
+/// three additions per coordinate and step and never drifts. The values are
+/// kept in `i128`, which holds any `isize` coordinate scaled by `MAX_STEPS`
+/// cubed.
+pub(crate) struct Bezier {
+    values: [[i128; 4]; 2],
+    divisor: i128,
+    remaining: usize,
+}
+
+impl Bezier {
+    /// Creates an iterator over a quadratic curve, with enough steps to
+    /// move at most a few pixels at a time, up to `MAX_STEPS`.
+    pub fn quadratic(
+        p0: Coordinates<isize>,
+        p1: Coordinates<isize>,
+        p2: Coordinates<isize>,
+    ) -> Self {
+        let steps = Self::steps(&[p0, p1, p2]);
+        let n = steps as i128;
+        Self::new(steps, n * n, |i, axis| {
+            let [a, b, c] = [p0, p1, p2].map(|point| coordinate(point, axis));
+            (n - i) * (n - i) * a + 2 * i * (n - i) * b + i * i * c
+        })
+    }
+
+    /// Creates an iterator over a cubic curve, with enough steps to move
+    /// at most a few pixels at a time, up to `MAX_STEPS`.
+    pub fn cubic(
+        p0: Coordinates<isize>,
+        p1: Coordinates<isize>,
+        p2: Coordinates<isize>,
+        p3: Coordinates<isize>,
+    ) -> Self {
+        let steps = Self::steps(&[p0, p1, p2, p3]);
+        let n = steps as i128;
+        Self::new(steps, n * n * n, |i, axis| {
+            let [a, b, c, d] = [p0, p1, p2, p3].map(|point| coordinate(point, axis));
+            let j = n - i;
+            j * j * j * a + 3 * i * j * j * b + 3 * i * i * j * c + i * i * i * d
+        })
+    }
+
+    /// Builds the forward difference table from the first four values of
+    /// the scaled polynomial `value(i, axis)`.
+    fn new(steps: usize, divisor: i128, value: impl Fn(i128, usize) -> i128) -> Self {
+        let mut values = [[0; 4]; 2];
+        for (axis, differences) in values.iter_mut().enumerate() {
+            for (i, difference) in differences.iter_mut().enumerate() {
+                *difference = value(i as i128, axis);
+            }
+            for order in 1..4 {
+                for i in (order..4).rev() {
+                    differences[i] -= differences[i - 1];
+                }
+            }
+        }
+        Bezier {
+            values,
+            divisor,
+            remaining: steps + 1,
+        }
+    }
+
+    /// Returns the number of steps for a curve with the given control points,
+    /// which is the larger side of their bounding box, capped at `MAX_STEPS`.
+    fn steps(points: &[Coordinates<isize>]) -> usize {
+        let extent = |axis: usize| {
+            let coordinates = points.iter().map(|&point| coordinate(point, axis));
+            let min = coordinates.clone().min().unwrap_or(0);
+            let max = coordinates.max().unwrap_or(0);
+            max.abs_diff(min)
+        };
+        extent(0).max(extent(1)).clamp(1, MAX_STEPS as u128) as usize
+    }
+}
+
+impl Iterator for Bezier {
+    type Item = Coordinates<isize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let divisor = self.divisor;
+        let [x, y] = self
+            .values
+            .map(|differences| (2 * differences[0] + divisor).div_euclid(2 * divisor) as isize);
+        for differences in self.values.iter_mut() {
+            for i in 0..3 {
+                differences[i] += differences[i + 1];
+            }
+        }
+        Some((x, y))
+    }
+}
+
+fn coordinate(point: Coordinates<isize>, axis: usize) -> i128 {
+    if axis == 0 {
+        point.0 as i128
+    } else {
+        point.1 as i128
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bezier_endpoints() {
+        let mut curve = Bezier::quadratic((10, 20), (50, -30), (90, 20));
+        assert_eq!(curve.next(), Some((10, 20)));
+        assert_eq!(curve.last(), Some((90, 20)));
+
+        let mut curve = Bezier::cubic((0, 0), (0, 100), (100, 100), (100, 0));
+        assert_eq!(curve.next(), Some((0, 0)));
+        assert_eq!(curve.last(), Some((100, 0)));
+    }
+
+    #[test]
+    fn test_bezier_quadratic_points() {
+        // The midpoint is `(p0 + 2 * p1 + p2) / 4`.
+        let mut curve = Bezier::quadratic((0, 0), (40, 80), (80, 0));
+        assert_eq!(curve.nth(40), Some((40, 40)));
+
+        // Control points on a line give a straight line.
+        let curve = Bezier::quadratic((0, 0), (5, 5), (10, 10));
+        assert!(curve.eq((0..=10).map(|i| (i, i))));
+    }
+
+    #[test]
+    fn test_bezier_cubic_points() {
+        // The midpoint is `(p0 + 3 * p1 + 3 * p2 + p3) / 8`.
+        let mut curve = Bezier::cubic((0, 0), (0, 80), (80, 80), (80, 0));
+        assert_eq!(curve.nth(40), Some((40, 60)));
+    }
+
+    #[test]
+    fn test_bezier_far_control_points() {
+        // The endpoints are still hit, in at most `MAX_STEPS` steps.
+        fn check(curve: impl Fn() -> Bezier, start: Coordinates<isize>, end: Coordinates<isize>) {
+            assert_eq!(curve().count(), MAX_STEPS + 1);
+            assert_eq!(curve().next(), Some(start));
+            assert_eq!(curve().last(), Some(end));
+        }
+
+        check(
+            || Bezier::quadratic((0, 0), (100_000, -100_000), (10, 0)),
+            (0, 0),
+            (10, 0),
+        );
+        check(
+            || Bezier::cubic((0, 0), (-100_000, 100_000), (100_000, 100_000), (10, 0)),
+            (0, 0),
+            (10, 0),
+        );
+        check(
+            || {
+                Bezier::cubic(
+                    (isize::MIN, isize::MAX),
+                    (isize::MAX, isize::MIN),
+                    (isize::MIN, isize::MIN),
+                    (isize::MAX, isize::MAX),
+                )
+            },
+            (isize::MIN, isize::MAX),
+            (isize::MAX, isize::MAX),
+        );
+    }
+
+    #[test]
+    fn test_bezier_single_point() {
+        let curve = Bezier::cubic((3, 4), (3, 4), (3, 4), (3, 4));
+        assert!(curve.eq([(3, 4), (3, 4)]));
+    }
+}
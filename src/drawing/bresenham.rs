@@ -1,7 +1,7 @@
-use super::{Octant, Point, SignedNum};
+use super::{Coordinates, Octant, SignedNum};
 
 pub(crate) struct Bresenham<T> {
-    point: Point<T>,
+    point: Coordinates<T>,
     end_x: T,
     delta_x: T,
     delta_y: T,
@@ -11,7 +11,7 @@ pub(crate) struct Bresenham<T> {
 
 impl<T: SignedNum> Bresenham<T> {
     #[inline]
-    pub fn new(start: Point<T>, end: Point<T>) -> Self {
+    pub fn new(start: Coordinates<T>, end: Coordinates<T>) -> Self {
         let octant = Octant::new(start, end);
         let start = octant.to(start);
         let end = octant.to(end);
@@ -34,7 +34,7 @@ impl<T> Iterator for Bresenham<T>
 where
     T: SignedNum,
 {
-    type Item = Point<T>;
+    type Item = Coordinates<T>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
@@ -0,0 +1,72 @@
+use super::{Coordinates, SignedNum};
+
+/// Iterates over the points of the first octant of a circle centered on
+/// `(0, 0)`, from `(radius, 0)` until `x == y`, using the midpoint algorithm.
+/// The remaining octants can be derived through symmetry.
+pub(crate) struct Circle<T> {
+    x: T,
+    y: T,
+    error: T,
+}
+
+impl<T: SignedNum> Circle<T> {
+    #[inline]
+    pub fn new(radius: T) -> Self {
+        Self {
+            x: radius,
+            y: T::zero(),
+            error: T::one() - radius,
+        }
+    }
+}
+
+impl<T> Iterator for Circle<T>
+where
+    T: SignedNum,
+{
+    type Item = Coordinates<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.y {
+            let point = (self.x, self.y);
+
+            self.y += T::one();
+            if self.error < T::zero() {
+                self.error += self.y + self.y + T::one();
+            } else {
+                self.x -= T::one();
+                self.error += (self.y - self.x) + (self.y - self.x) + T::one();
+            }
+
+            Some(point)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_circle_radius_zero() {
+        let mut circle = Circle::new(0isize);
+        assert_eq!(circle.next(), Some((0, 0)));
+        assert_eq!(circle.next(), None);
+    }
+
+    #[test]
+    fn test_circle_points_are_on_radius() {
+        let radius = 10isize;
+        let mut last = (radius + 1, -1);
+        for (x, y) in Circle::new(radius) {
+            assert!(x >= y);
+            assert!((x * x + y * y - radius * radius).abs() <= radius);
+            assert_eq!(y, last.1 + 1);
+            assert!(last.0 - x <= 1);
+            last = (x, y);
+        }
+    }
+}
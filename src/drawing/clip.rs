@@ -0,0 +1,190 @@
+use super::{Coordinates, Point, Rect};
+
+const INSIDE: u8 = 0b0000;
+const LEFT: u8 = 0b0001;
+const RIGHT: u8 = 0b0010;
+const TOP: u8 = 0b0100;
+const BOTTOM: u8 = 0b1000;
+
+/// The inclusive bounds of a non-empty `Rect`, widened to avoid overflow
+/// while solving for the intersections.
+struct Bounds {
+    x_min: i128,
+    y_min: i128,
+    x_max: i128,
+    y_max: i128,
+}
+
+impl Bounds {
+    fn outcode(&self, x: i128, y: i128) -> u8 {
+        let mut code = INSIDE;
+        if x < self.x_min {
+            code |= LEFT;
+        } else if x > self.x_max {
+            code |= RIGHT;
+        }
+        if y < self.y_min {
+            code |= TOP;
+        } else if y > self.y_max {
+            code |= BOTTOM;
+        }
+        code
+    }
+}
+
+/// Clips the line from `start` to `end` to `rect` using the Cohen-Sutherland
+/// algorithm, returning the end points of the visible part of the line, or
+/// `None` if none of it is visible.
+///
+/// Off-screen end points are moved along the line to the edge they cross,
+/// rounded to the nearest pixel, which keeps the slope of the visible part.
+pub(crate) fn clip_line(
+    start: impl Into<Point>,
+    end: impl Into<Point>,
+    rect: &Rect,
+) -> Option<(Coordinates<isize>, Coordinates<isize>)> {
+    if rect.is_empty() {
+        return None;
+    }
+
+    let bounds = Bounds {
+        x_min: rect.x as i128,
+        y_min: rect.y as i128,
+        x_max: rect.right() as i128 - 1,
+        y_max: rect.bottom() as i128 - 1,
+    };
+    let (start, end) = (start.into(), end.into());
+    let (mut x0, mut y0) = (start.x as i128, start.y as i128);
+    let (mut x1, mut y1) = (end.x as i128, end.y as i128);
+    let mut code0 = bounds.outcode(x0, y0);
+    let mut code1 = bounds.outcode(x1, y1);
+
+    loop {
+        if code0 | code1 == INSIDE {
+            return Some(((x0 as isize, y0 as isize), (x1 as isize, y1 as isize)));
+        }
+        if code0 & code1 != INSIDE {
+            return None;
+        }
+
+        // Move an end point that lies outside onto the edge it crosses.
+        let code = if code0 != INSIDE { code0 } else { code1 };
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let (x, y) = if code & TOP != 0 {
+            (x0 + div_round(dx * (bounds.y_min - y0), dy), bounds.y_min)
+        } else if code & BOTTOM != 0 {
+            (x0 + div_round(dx * (bounds.y_max - y0), dy), bounds.y_max)
+        } else if code & LEFT != 0 {
+            (bounds.x_min, y0 + div_round(dy * (bounds.x_min - x0), dx))
+        } else {
+            (bounds.x_max, y0 + div_round(dy * (bounds.x_max - x0), dx))
+        };
+
+        if code == code0 {
+            x0 = x;
+            y0 = y;
+            code0 = bounds.outcode(x0, y0);
+        } else {
+            x1 = x;
+            y1 = y;
+            code1 = bounds.outcode(x1, y1);
+        }
+    }
+}
+
+/// Divides `numerator` by `denominator`, rounding to the nearest integer.
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let (numerator, denominator) = if denominator < 0 {
+        (-numerator, -denominator)
+    } else {
+        (numerator, denominator)
+    };
+    (2 * numerator + denominator).div_euclid(2 * denominator)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SCREEN: Rect = Rect::new(0, 0, 640, 480);
+
+    #[test]
+    fn test_clip_line_inside() {
+        assert_eq!(
+            clip_line((10, 20), (600, 400), &SCREEN),
+            Some(((10, 20), (600, 400)))
+        );
+        assert_eq!(clip_line((5, 5), (5, 5), &SCREEN), Some(((5, 5), (5, 5))));
+    }
+
+    #[test]
+    fn test_clip_line_edges() {
+        // Left, right, top and bottom edges.
+        assert_eq!(
+            clip_line((-100, 100), (100, 100), &SCREEN),
+            Some(((0, 100), (100, 100)))
+        );
+        assert_eq!(
+            clip_line((600, 50), (700, 150), &SCREEN),
+            Some(((600, 50), (639, 89)))
+        );
+        assert_eq!(
+            clip_line((100, -100), (300, 100), &SCREEN),
+            Some(((200, 0), (300, 100)))
+        );
+        assert_eq!(
+            clip_line((320, 400), (320, 1000), &SCREEN),
+            Some(((320, 400), (320, 479)))
+        );
+    }
+
+    #[test]
+    fn test_clip_line_corners() {
+        assert_eq!(
+            clip_line((-10, -10), (10, 10), &SCREEN),
+            Some(((0, 0), (10, 10)))
+        );
+        // Just misses the bottom left corner, crossing the top edge instead
+        // of the right one.
+        assert_eq!(
+            clip_line((-100, 580), (740, -260), &SCREEN),
+            Some(((1, 479), (480, 0)))
+        );
+        assert_eq!(
+            clip_line((-1000, -1000), (2000, 2000), &SCREEN),
+            Some(((0, 0), (479, 479)))
+        );
+    }
+
+    #[test]
+    fn test_clip_line_outside() {
+        assert_eq!(clip_line((-10, -10), (-1, 500), &SCREEN), None);
+        assert_eq!(clip_line((700, 10), (640, 400), &SCREEN), None);
+        // Passes just above the top right corner.
+        assert_eq!(clip_line((600, -50), (700, 30), &SCREEN), None);
+        assert_eq!(clip_line((0, 0), (10, 10), &Rect::new(5, 5, 0, 10)), None);
+    }
+
+    #[test]
+    fn test_clip_line_stays_on_line() {
+        let lines = [
+            ((-500, 37), (900, 411)),
+            ((320, -1234), (17, 5000)),
+            ((-3, -7), (651, 491)),
+            ((isize::MIN, 0), (isize::MAX, 479)),
+        ];
+        for &(start, end) in lines.iter() {
+            let ((x0, y0), (x1, y1)) = clip_line(start, end, &SCREEN).unwrap();
+            for &(x, y) in [(x0, y0), (x1, y1)].iter() {
+                assert!(SCREEN.contains((x, y)));
+                // The clipped end points are within a pixel of the original line.
+                let (dx, dy) = (
+                    end.0 as i128 - start.0 as i128,
+                    end.1 as i128 - start.1 as i128,
+                );
+                let cross = dx * (y as i128 - start.1 as i128) - dy * (x as i128 - start.0 as i128);
+                assert!(cross.abs() <= dx.abs().max(dy.abs()));
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Coordinates, SignedNum};
+
+/// Iterates over the points of the first quadrant of an axis-aligned ellipse
+/// centered on `(0, 0)`, from `(0, ry)` to `(rx, 0)`, using the two region
+/// midpoint algorithm. The remaining quadrants can be derived through symmetry.
+///
+/// The decision variable is scaled by 4 to keep it an integer. A radius of 0
+/// degrades to a line along the other axis.
+pub(crate) struct Ellipse<T> {
+    x: T,
+    y: T,
+    rx: T,
+    rx2: T,
+    ry2: T,
+    dx: T,
+    dy: T,
+    error: T,
+    first_region: bool,
+}
+
+impl<T: SignedNum> Ellipse<T> {
+    #[inline]
+    pub fn new(rx: T, ry: T) -> Self {
+        let four = T::cast(4);
+        let rx2 = rx * rx;
+        let ry2 = ry * ry;
+        let dy = (rx2 + rx2) * ry;
+        let mut ellipse = Self {
+            x: T::zero(),
+            y: ry,
+            rx,
+            rx2,
+            ry2,
+            dx: T::zero(),
+            dy,
+            error: four * ry2 - four * rx2 * ry + rx2,
+            first_region: true,
+        };
+        if dy <= T::zero() {
+            ellipse.start_second_region();
+        }
+        ellipse
+    }
+
+    #[inline]
+    fn start_second_region(&mut self) {
+        let four = T::cast(4);
+        let x = self.x + self.x + T::one();
+        let y = self.y - T::one();
+        self.first_region = false;
+        self.error = self.ry2 * x * x + four * self.rx2 * y * y - four * self.rx2 * self.ry2;
+    }
+}
+
+impl<T> Iterator for Ellipse<T>
+where
+    T: SignedNum,
+{
+    type Item = Coordinates<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let four = T::cast(4);
+        if self.y < T::zero() {
+            // Very flat ellipses may leave the second region before reaching
+            // `(rx, 0)`, finish the last row along the x axis.
+            if self.x < self.rx {
+                self.x += T::one();
+                return Some((self.x, T::zero()));
+            }
+            return None;
+        }
+
+        let point = (self.x, self.y);
+        if self.first_region {
+            self.x += T::one();
+            self.dx += self.ry2 + self.ry2;
+            if self.error < T::zero() {
+                self.error += four * (self.dx + self.ry2);
+            } else {
+                self.y -= T::one();
+                self.dy -= self.rx2 + self.rx2;
+                self.error += four * (self.dx - self.dy + self.ry2);
+            }
+            if self.dx >= self.dy {
+                self.start_second_region();
+            }
+        } else {
+            self.y -= T::one();
+            self.dy -= self.rx2 + self.rx2;
+            if self.error > T::zero() {
+                self.error += four * (self.rx2 - self.dy);
+            } else {
+                self.x += T::one();
+                self.dx += self.ry2 + self.ry2;
+                self.error += four * (self.dx - self.dy + self.rx2);
+            }
+        }
+
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_connected(rx: isize, ry: isize) {
+        let mut last: Option<Coordinates<isize>> = None;
+        for (x, y) in Ellipse::new(rx, ry) {
+            match last {
+                Some((last_x, last_y)) => {
+                    assert!(x - last_x == 0 || x - last_x == 1);
+                    assert!(last_y - y == 0 || last_y - y == 1);
+                    assert_ne!((x, y), (last_x, last_y));
+                }
+                None => assert_eq!((x, y), (0, ry)),
+            }
+            last = Some((x, y));
+        }
+        assert_eq!(last, Some((rx, 0)));
+    }
+
+    #[test]
+    fn test_ellipse_radius_zero() {
+        let mut ellipse = Ellipse::new(0isize, 0);
+        assert_eq!(ellipse.next(), Some((0, 0)));
+        assert_eq!(ellipse.next(), None);
+
+        assert!(Ellipse::new(0isize, 3).eq([(0, 3), (0, 2), (0, 1), (0, 0)]));
+        assert!(Ellipse::new(3isize, 0).eq([(0, 0), (1, 0), (2, 0), (3, 0)]));
+    }
+
+    #[test]
+    fn test_ellipse_points_are_connected() {
+        for &(rx, ry) in &[(1, 1), (10, 10), (20, 5), (5, 20), (100, 1), (1, 100)] {
+            assert_connected(rx, ry);
+        }
+    }
+
+    #[test]
+    fn test_ellipse_points_are_on_ellipse() {
+        let (rx, ry) = (30isize, 12isize);
+        for (x, y) in Ellipse::new(rx, ry) {
+            let distance = x * x * ry * ry + y * y * rx * rx - rx * rx * ry * ry;
+            assert!(distance.abs() <= rx * ry * (rx + ry));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::Rect;
+use crate::dither::BAYER_4X4;
+
+/// The axis along which a gradient changes from its start to its end color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GradientDirection {
+    /// The gradient runs from the left edge to the right edge.
+    Horizontal,
+    /// The gradient runs from the top edge to the bottom edge.
+    Vertical,
+}
+
+impl GradientDirection {
+    /// Returns how far along the gradient over `rect` the pixel at `(x, y)`
+    /// is, from 0 at the start to 16 at the end, rounded to the nearest step.
+    pub(crate) fn level(self, rect: &Rect, x: usize, y: usize) -> u8 {
+        let (position, length) = match self {
+            GradientDirection::Horizontal => ((x as isize - rect.x) as usize, rect.width),
+            GradientDirection::Vertical => ((y as isize - rect.y) as usize, rect.height),
+        };
+        if length <= 1 {
+            return 0;
+        }
+        let last = length - 1;
+        ((position * 16 + last / 2) / last) as u8
+    }
+}
+
+/// Returns `true` if the pixel at `(x, y)` takes the end color of a gradient
+/// at `level`, which happens for `level` out of every 16 pixels.
+pub(crate) fn gradient_dither(x: usize, y: usize, level: u8) -> bool {
+    BAYER_4X4[y % 4][x % 4] < level
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gradient_level() {
+        let rect = Rect::new(10, 20, 33, 5);
+        let horizontal = GradientDirection::Horizontal;
+        assert_eq!(horizontal.level(&rect, 10, 22), 0);
+        assert_eq!(horizontal.level(&rect, 26, 22), 8);
+        assert_eq!(horizontal.level(&rect, 42, 22), 16);
+
+        let vertical = GradientDirection::Vertical;
+        assert_eq!(vertical.level(&rect, 30, 20), 0);
+        assert_eq!(vertical.level(&rect, 30, 22), 8);
+        assert_eq!(vertical.level(&rect, 30, 24), 16);
+
+        let line = Rect::new(0, 0, 1, 1);
+        assert_eq!(horizontal.level(&line, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_gradient_dither() {
+        for level in 0..=16 {
+            let count = (0..16)
+                .filter(|i| gradient_dither(i % 4, i / 4, level))
+                .count();
+            assert_eq!(count, usize::from(level));
+        }
+        // Every pixel at one level keeps the end color at higher levels.
+        for level in 1..16 {
+            for i in 0..16 {
+                let (x, y) = (i % 4 + 8, i / 4 + 4);
+                assert!(!gradient_dither(x, y, level) || gradient_dither(x, y, level + 1));
+            }
+        }
+    }
+}
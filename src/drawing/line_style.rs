@@ -0,0 +1,66 @@
+/// The pattern used to draw a line, measured in pixels along the line.
+///
+/// Each pixel drawn by a line has a phase, which counts up from 0 at its
+/// start and decides whether the pixel is drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineStyle {
+    /// Every pixel of the line is drawn.
+    #[default]
+    Solid,
+    /// `on` pixels are drawn followed by `off` pixels that are skipped.
+    Dashed {
+        /// The number of pixels drawn in each dash.
+        on: usize,
+        /// The number of pixels skipped between dashes.
+        off: usize,
+    },
+    /// Every other pixel of the line is drawn.
+    Dotted,
+}
+
+impl LineStyle {
+    /// Returns whether the pixel at `phase` is drawn.
+    pub const fn is_on(&self, phase: usize) -> bool {
+        match *self {
+            LineStyle::Solid => true,
+            LineStyle::Dashed { on, off } => {
+                let period = on.saturating_add(off);
+                period == 0 || phase % period < on
+            }
+            LineStyle::Dotted => phase.is_multiple_of(2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pattern(style: LineStyle) -> [bool; 8] {
+        let mut pattern = [false; 8];
+        for (phase, on) in pattern.iter_mut().enumerate() {
+            *on = style.is_on(phase);
+        }
+        pattern
+    }
+
+    #[test]
+    fn test_line_style_is_on() {
+        assert_eq!(pattern(LineStyle::Solid), [true; 8]);
+        assert_eq!(
+            pattern(LineStyle::Dotted),
+            [true, false, true, false, true, false, true, false]
+        );
+        assert_eq!(
+            pattern(LineStyle::Dashed { on: 3, off: 2 }),
+            [true, true, true, false, false, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_line_style_degenerate_dashes() {
+        assert_eq!(pattern(LineStyle::Dashed { on: 0, off: 0 }), [true; 8]);
+        assert_eq!(pattern(LineStyle::Dashed { on: 0, off: 4 }), [false; 8]);
+        assert_eq!(pattern(LineStyle::Dashed { on: 4, off: 0 }), [true; 8]);
+    }
+}
@@ -2,14 +2,37 @@
 //! Original implementation here https://github.com/expenses/line_drawing.
 use num_traits::{NumAssignOps, NumCast, Signed};
 
+mod bezier;
 mod bresenham;
+mod circle;
+mod clip;
+mod ellipse;
+mod gradient;
+mod line_style;
 mod octant;
+mod point;
+mod rect;
+mod text_layout;
+mod thick_line;
 
+pub(crate) use bezier::Bezier;
 pub(crate) use bresenham::Bresenham;
+pub(crate) use circle::Circle;
+pub(crate) use clip::clip_line;
+pub(crate) use ellipse::Ellipse;
+pub(crate) use gradient::gradient_dither;
+pub use gradient::GradientDirection;
+pub use line_style::LineStyle;
 use octant::Octant;
+pub use point::Point;
+pub use rect::Rect;
+pub use text_layout::TextAlignment;
+pub(crate) use text_layout::WrappedLines;
+pub use thick_line::LineCap;
+pub(crate) use thick_line::ThickLine;
 
-/// A point in 2D space.
-pub type Point<T> = (T, T);
+/// A pair of `(x, y)` coordinates, as used by the line and curve iterators.
+pub(crate) type Coordinates<T> = (T, T);
 
 pub(crate) trait SignedNum: Signed + Ord + Copy + NumCast + NumAssignOps {
     fn cast<T: NumCast>(value: T) -> Self {
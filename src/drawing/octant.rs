@@ -1,4 +1,4 @@
-use super::Point;
+use super::Coordinates;
 use core::ops::{Neg, Sub};
 use num_traits::Zero;
 
@@ -10,7 +10,7 @@ pub struct Octant {
 impl Octant {
     #[inline]
     /// Get the relevant octant from a start and end point.
-    pub fn new<T>(start: Point<T>, end: Point<T>) -> Self
+    pub fn new<T>(start: Coordinates<T>, end: Coordinates<T>) -> Self
     where
         T: Sub<Output = T> + Neg<Output = T> + PartialOrd + Zero,
     {
@@ -40,7 +40,7 @@ impl Octant {
 
     /// Convert a point to its position in the octant.
     #[inline]
-    pub fn to<T>(&self, point: Point<T>) -> Point<T>
+    pub fn to<T>(&self, point: Coordinates<T>) -> Coordinates<T>
     where
         T: Neg<Output = T>,
     {
@@ -59,7 +59,7 @@ impl Octant {
 
     /// Convert a point from its position in the octant.
     #[inline]
-    pub fn from<T: Neg<Output = T>>(&self, point: Point<T>) -> Point<T> {
+    pub fn from<T: Neg<Output = T>>(&self, point: Coordinates<T>) -> Coordinates<T> {
         match self.value {
             0 => (point.0, point.1),
             1 => (point.1, point.0),
@@ -0,0 +1,57 @@
+/// A point on the screen, with `x` increasing to the right and `y`
+/// increasing downwards.
+///
+/// The coordinates are signed, so shapes can start above or left of the
+/// screen and be clipped. A `Point` can be created from an `(x, y)` tuple,
+/// so APIs taking `impl Into<Point>` also accept tuples.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    /// The x coordinate.
+    pub x: isize,
+    /// The y coordinate.
+    pub y: isize,
+}
+
+impl Point {
+    /// Creates a new `Point` at `(x, y)`.
+    pub const fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Point {
+        Point::new(x as isize, y as isize)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Point {
+        Point::new(x as isize, y as isize)
+    }
+}
+
+impl From<Point> for (isize, isize) {
+    fn from(point: Point) -> (isize, isize) {
+        (point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point_from_tuples() {
+        assert_eq!(Point::from((3, -4)), Point::new(3, -4));
+        assert_eq!(Point::from((3isize, -4isize)), Point::new(3, -4));
+        assert_eq!(Point::from((3usize, 4usize)), Point::new(3, 4));
+        assert_eq!(<(isize, isize)>::from(Point::new(-1, 2)), (-1, 2));
+    }
+}
@@ -0,0 +1,138 @@
+use super::Point;
+use core::ops::Range;
+
+/// An axis-aligned rectangle with its top left corner at `(x, y)`.
+///
+/// The corner is signed, so a `Rect` can start above or left of the screen.
+/// A `Rect` can be created from a `(x, y, width, height)` tuple, so APIs
+/// taking `impl Into<Rect>` also accept tuples.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The x coordinate of the left edge.
+    pub x: isize,
+    /// The y coordinate of the top edge.
+    pub y: isize,
+    /// The width in pixels.
+    pub width: usize,
+    /// The height in pixels.
+    pub height: usize,
+}
+
+impl Rect {
+    /// Creates a new `Rect` with its top left corner at `(x, y)` and
+    /// the given `width` and `height`.
+    pub const fn new(x: isize, y: isize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a new `Rect` with its top left corner at `top_left` and
+    /// the given `width` and `height`.
+    pub fn from_point(top_left: impl Into<Point>, width: usize, height: usize) -> Rect {
+        let top_left = top_left.into();
+        Rect::new(top_left.x, top_left.y, width, height)
+    }
+
+    /// Returns the top left corner of the `Rect`.
+    pub const fn top_left(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Returns the x coordinate just past the right edge of the `Rect`.
+    pub const fn right(&self) -> isize {
+        self.x.saturating_add_unsigned(self.width)
+    }
+
+    /// Returns the y coordinate just past the bottom edge of the `Rect`.
+    pub const fn bottom(&self) -> isize {
+        self.y.saturating_add_unsigned(self.height)
+    }
+
+    /// Returns `true` if the `Rect` doesn't cover any pixels.
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns `true` if `point` lies within the `Rect`.
+    pub fn contains(&self, point: impl Into<Point>) -> bool {
+        let Point { x, y } = point.into();
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    /// Returns the part of the `Rect` that also lies within `other`, which
+    /// is empty if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Rect {
+            x,
+            y,
+            width: right.saturating_sub(x).max(0) as usize,
+            height: bottom.saturating_sub(y).max(0) as usize,
+        }
+    }
+
+    /// Returns the columns covered by the `Rect`, leaving out any left of
+    /// the screen.
+    pub(crate) fn columns(&self) -> Range<usize> {
+        self.x.max(0) as usize..self.right().max(0) as usize
+    }
+
+    /// Returns the rows covered by the `Rect`, leaving out any above the
+    /// screen.
+    pub(crate) fn rows(&self) -> Range<usize> {
+        self.y.max(0) as usize..self.bottom().max(0) as usize
+    }
+}
+
+impl From<(isize, isize, usize, usize)> for Rect {
+    fn from((x, y, width, height): (isize, isize, usize, usize)) -> Rect {
+        Rect::new(x, y, width, height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rect_contains() {
+        let rect = Rect::new(10, 20, 30, 40);
+        assert!(rect.contains((10, 20)));
+        assert!(rect.contains((39, 59)));
+        assert!(!rect.contains((40, 20)));
+        assert!(!rect.contains((10, 60)));
+        assert!(!rect.contains((-1, 20)));
+        assert!(!Rect::new(5, 5, 0, 10).contains((5, 5)));
+        assert!(Rect::new(-10, -10, 20, 20).contains((-5, 9)));
+    }
+
+    #[test]
+    fn test_rect_intersection() {
+        let rect = Rect::from((10, 20, 30, 40));
+        assert_eq!(
+            rect.intersection(&Rect::new(0, 30, 20, usize::MAX)),
+            Rect::new(10, 30, 10, 30)
+        );
+        assert!(rect.intersection(&Rect::new(50, 0, 10, 10)).is_empty());
+        assert_eq!(Rect::new(isize::MAX, 0, 10, 1).right(), isize::MAX);
+        assert_eq!(
+            Rect::new(-5, -8, 10, 10).intersection(&Rect::new(0, 0, 640, 480)),
+            Rect::new(0, 0, 5, 2)
+        );
+    }
+
+    #[test]
+    fn test_rect_columns_and_rows() {
+        let rect = Rect::new(-5, 3, 10, 4);
+        assert_eq!(rect.columns(), 0..5);
+        assert_eq!(rect.rows(), 3..7);
+        assert!(Rect::new(-20, -20, 10, 10).columns().is_empty());
+    }
+}
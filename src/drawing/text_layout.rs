@@ -0,0 +1,127 @@
+/// The horizontal placement of each line of text within its rectangle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextAlignment {
+    /// Lines start at the left edge.
+    #[default]
+    Left,
+    /// Lines are centered, rounding towards the left edge.
+    Center,
+    /// Lines end at the right edge.
+    Right,
+}
+
+impl TextAlignment {
+    /// Returns how far from the left edge of a rectangle `width` pixels
+    /// wide a line `line_width` pixels wide starts.
+    pub(crate) fn offset(self, width: usize, line_width: usize) -> usize {
+        let space = width.saturating_sub(line_width);
+        match self {
+            TextAlignment::Left => 0,
+            TextAlignment::Center => space / 2,
+            TextAlignment::Right => space,
+        }
+    }
+}
+
+/// Splits text into lines of at most `columns` characters.
+///
+/// Lines end at each `\n`, and lines that are too long are broken at the
+/// last space that fits, which is dropped along with any spaces that would
+/// start the next line. Words longer than a whole line are broken wherever
+/// the line is full.
+pub(crate) struct WrappedLines<'a> {
+    text: Option<&'a str>,
+    columns: usize,
+}
+
+impl<'a> WrappedLines<'a> {
+    pub fn new(text: &'a str, columns: usize) -> Self {
+        WrappedLines {
+            text: if columns == 0 { None } else { Some(text) },
+            columns,
+        }
+    }
+}
+
+impl<'a> Iterator for WrappedLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.text?;
+        let mut last_space = None;
+        for (column, (index, character)) in text.char_indices().enumerate() {
+            if character == '\n' {
+                self.text = Some(&text[index + 1..]);
+                return Some(&text[..index]);
+            }
+            if column == self.columns {
+                // Break at this space or the last one, unless that would leave
+                // the line empty, in which case the word itself is broken.
+                let end = match character {
+                    ' ' => index,
+                    _ => last_space.unwrap_or(index),
+                };
+                let end = if text[..end].trim_end().is_empty() {
+                    index
+                } else {
+                    end
+                };
+                let rest = text[end..].trim_start_matches(' ');
+                self.text = Some(rest.strip_prefix('\n').unwrap_or(rest));
+                return Some(text[..end].trim_end_matches(' '));
+            }
+            if character == ' ' {
+                last_space = Some(index);
+            }
+        }
+        self.text = None;
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_text_alignment_offset() {
+        assert_eq!(TextAlignment::Left.offset(100, 40), 0);
+        assert_eq!(TextAlignment::Center.offset(100, 40), 30);
+        assert_eq!(TextAlignment::Center.offset(100, 41), 29);
+        assert_eq!(TextAlignment::Right.offset(100, 40), 60);
+        assert_eq!(TextAlignment::Right.offset(100, 140), 0);
+    }
+
+    #[test]
+    fn test_wrapped_lines_words() {
+        let lines = WrappedLines::new("the quick brown fox jumps", 10);
+        assert!(lines.eq(["the quick", "brown fox", "jumps"]));
+
+        let lines = WrappedLines::new("exactly ten and more", 11);
+        assert!(lines.eq(["exactly ten", "and more"]));
+
+        let lines = WrappedLines::new("spaced    out", 8);
+        assert!(lines.eq(["spaced", "out"]));
+    }
+
+    #[test]
+    fn test_wrapped_lines_newlines() {
+        let lines = WrappedLines::new("one\n\ntwo three\n", 5);
+        assert!(lines.eq(["one", "", "two", "three", ""]));
+
+        // A newline right where a line is wrapped doesn't add an empty line.
+        let lines = WrappedLines::new("abcd efgh\nij", 4);
+        assert!(lines.eq(["abcd", "efgh", "ij"]));
+    }
+
+    #[test]
+    fn test_wrapped_lines_long_words() {
+        let lines = WrappedLines::new("abcdefghij kl", 4);
+        assert!(lines.eq(["abcd", "efgh", "ij", "kl"]));
+
+        let lines = WrappedLines::new("  abcdefgh", 4);
+        assert!(lines.eq(["  ab", "cdef", "gh"]));
+
+        assert_eq!(WrappedLines::new("anything", 0).next(), None);
+    }
+}
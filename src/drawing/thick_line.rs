@@ -0,0 +1,200 @@
+use super::Coordinates;
+use core::ops::Range;
+
+const FRACTION_BITS: u32 = 8;
+const ONE: i64 = 1 << FRACTION_BITS;
+
+/// The shape drawn at both ends of a thick line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// The line ends exactly at its endpoints.
+    Butt,
+    /// The line is extended past its endpoints by half its thickness,
+    /// so a line with a thickness of 1 covers the same pixels as a
+    /// regular line.
+    #[default]
+    Square,
+    /// The line ends in a half circle centered on each endpoint.
+    Round,
+}
+
+/// The outline of a line with a thickness, which is filled one horizontal
+/// span per row. Coordinates are kept in fixed point with `FRACTION_BITS`
+/// fractional bits, with pixel centers on whole numbers.
+///
+/// The outline is a rectangle around the line, extended by the caps. It's
+/// always convex, so every row is covered by at most a single span.
+pub(crate) struct ThickLine {
+    corners: [Coordinates<i64>; 4],
+    round_caps: Option<[Coordinates<i64>; 2]>,
+    radius: i64,
+    rows: Range<isize>,
+}
+
+impl ThickLine {
+    pub fn new(
+        start: Coordinates<isize>,
+        end: Coordinates<isize>,
+        thickness: usize,
+        cap: LineCap,
+    ) -> Self {
+        let mut line = ThickLine {
+            corners: [(0, 0); 4],
+            round_caps: None,
+            radius: 0,
+            rows: 0..0,
+        };
+        if thickness == 0 || (start == end && cap == LineCap::Butt) {
+            return line;
+        }
+
+        let start = (start.0 as i64 * ONE, start.1 as i64 * ONE);
+        let end = (end.0 as i64 * ONE, end.1 as i64 * ONE);
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let half = thickness as i64 * ONE / 2;
+
+        // `(hx, hy)` points along the line and is half the thickness long,
+        // a single point is treated as a horizontal line.
+        let length = (dx * dx + dy * dy).isqrt();
+        let (hx, hy) = if length == 0 {
+            (half, 0)
+        } else {
+            (dx * half / length, dy * half / length)
+        };
+        let (ex, ey) = if cap == LineCap::Square {
+            (hx, hy)
+        } else {
+            (0, 0)
+        };
+
+        line.corners = [
+            (start.0 - ex - hy, start.1 - ey + hx),
+            (end.0 + ex - hy, end.1 + ey + hx),
+            (end.0 + ex + hy, end.1 + ey - hx),
+            (start.0 - ex + hy, start.1 - ey - hx),
+        ];
+        let mut top = line.corners.iter().map(|corner| corner.1).min().unwrap();
+        let mut bottom = line.corners.iter().map(|corner| corner.1).max().unwrap();
+        if cap == LineCap::Round {
+            line.round_caps = Some([start, end]);
+            line.radius = half;
+            top = top.min(start.1.min(end.1) - half);
+            bottom = bottom.max(start.1.max(end.1) + half);
+        }
+
+        // A row is covered if its center lies within `top..bottom`.
+        line.rows = ceil_pixel(top) as isize..ceil_pixel(bottom) as isize;
+        line
+    }
+
+    /// Returns the rows covered by the line.
+    pub fn rows(&self) -> Range<isize> {
+        self.rows.clone()
+    }
+
+    /// Returns the inclusive range of pixels covered by the line on row `y`.
+    pub fn span(&self, y: isize) -> Option<(isize, isize)> {
+        if !self.rows.contains(&y) {
+            return None;
+        }
+
+        let y = y as i64 * ONE;
+        let mut left = i64::MAX;
+        let mut right = i64::MIN;
+        let edges = self.corners.iter().zip(self.corners.iter().cycle().skip(1));
+        for (&(x0, y0), &(x1, y1)) in edges {
+            if y0 == y1 || y < y0.min(y1) || y > y0.max(y1) {
+                continue;
+            }
+            let x = x0 + (x1 - x0) * (y - y0) / (y1 - y0);
+            left = left.min(x);
+            right = right.max(x);
+        }
+        for &(cx, cy) in self.round_caps.iter().flatten() {
+            let distance = y - cy;
+            if distance.abs() < self.radius {
+                let half_width = (self.radius * self.radius - distance * distance).isqrt();
+                left = left.min(cx - half_width);
+                right = right.max(cx + half_width);
+            }
+        }
+
+        // A pixel is covered if its center lies within `left..right`.
+        if left >= right {
+            return None;
+        }
+        let (x_start, x_end) = (ceil_pixel(left), ceil_pixel(right) - 1);
+        if x_end < x_start {
+            return None;
+        }
+        Some((x_start as isize, x_end as isize))
+    }
+}
+
+/// Returns the first whole pixel at or after the fixed point `value`.
+fn ceil_pixel(value: i64) -> i64 {
+    -(-value).div_euclid(ONE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(line: &ThickLine) -> impl Iterator<Item = (isize, (isize, isize))> + '_ {
+        line.rows()
+            .filter_map(move |y| line.span(y).map(|span| (y, span)))
+    }
+
+    #[test]
+    fn test_thick_line_thickness_one() {
+        let line = ThickLine::new((0, 0), (10, 0), 1, LineCap::Square);
+        assert_eq!(line.rows(), 0..1);
+        assert_eq!(line.span(0), Some((0, 10)));
+
+        let line = ThickLine::new((5, 2), (5, 8), 1, LineCap::Square);
+        assert!(spans(&line).eq((2..=8).map(|y| (y, (5, 5)))));
+    }
+
+    #[test]
+    fn test_thick_line_caps() {
+        let line = ThickLine::new((10, 10), (20, 10), 3, LineCap::Butt);
+        assert_eq!(line.rows(), 9..12);
+        assert!(spans(&line).all(|(_, span)| span == (10, 19)));
+
+        let line = ThickLine::new((10, 10), (20, 10), 3, LineCap::Square);
+        assert!(spans(&line).all(|(_, span)| span == (9, 21)));
+
+        let line = ThickLine::new((10, 10), (20, 10), 5, LineCap::Round);
+        assert_eq!(line.rows(), 8..13);
+        assert_eq!(line.span(8), Some((9, 21)));
+        assert_eq!(line.span(10), Some((8, 22)));
+        assert_eq!(line.span(12), Some((9, 21)));
+    }
+
+    #[test]
+    fn test_thick_line_empty() {
+        let line = ThickLine::new((0, 0), (10, 10), 0, LineCap::Square);
+        assert!(line.rows().is_empty());
+
+        let line = ThickLine::new((5, 5), (5, 5), 4, LineCap::Butt);
+        assert!(line.rows().is_empty());
+
+        let line = ThickLine::new((5, 5), (5, 5), 3, LineCap::Square);
+        assert!(spans(&line).eq((4..7).map(|y| (y, (4, 6)))));
+    }
+
+    #[test]
+    fn test_thick_line_diagonal_has_no_gaps() {
+        for &thickness in &[1, 2, 3, 8] {
+            let line = ThickLine::new((0, 0), (37, 100), thickness, LineCap::Butt);
+            let mut last: Option<(isize, (isize, isize))> = None;
+            for (y, (x_start, x_end)) in spans(&line) {
+                if let Some((last_y, (last_start, last_end))) = last {
+                    assert_eq!(y, last_y + 1);
+                    assert!(x_start <= last_end + 1 && last_start <= x_end + 1);
+                }
+                last = Some((y, (x_start, x_end)));
+            }
+        }
+    }
+}
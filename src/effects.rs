@@ -0,0 +1,100 @@
+//! Building blocks for classic palette based demo effects.
+//!
+//! The effects work on indexed buffers with one byte per pixel, which can be
+//! drawn with `Graphics320x200x256::draw_image` after each step.
+
+use crate::colors::PALETTE_SIZE;
+
+/// Advances the fire effect in `buffer`, a `width` by `height` image of heat
+/// values stored row by row, by one frame.
+///
+/// Every pixel takes the average heat of the three pixels below it and the one
+/// two rows down, cooled by one, so the heat rises and fades out towards the
+/// top. The bottom row is the source of the fire and isn't changed, so it
+/// should be filled with fresh heat values before each call. Pixels on the
+/// left and right edges wrap around to the other side.
+///
+/// # Panics
+///
+/// Panics if `buffer` has fewer than `width * height` entries.
+pub fn propagate_fire(buffer: &mut [u8], width: usize, height: usize) {
+    assert!(
+        buffer.len() >= width * height,
+        "buffer is too small for the image"
+    );
+    if width == 0 || height < 2 {
+        return;
+    }
+
+    // Rows are updated from the top, so the rows below are still untouched.
+    for y in 0..height - 1 {
+        let below = (y + 1) * width;
+        let two_below = (y + 2).min(height - 1) * width;
+        for x in 0..width {
+            let left = (x + width - 1) % width;
+            let right = (x + 1) % width;
+            let sum = u16::from(buffer[below + left])
+                + u16::from(buffer[below + x])
+                + u16::from(buffer[below + right])
+                + u16::from(buffer[two_below + x]);
+            buffer[y * width + x] = (sum / 4).saturating_sub(1) as u8;
+        }
+    }
+}
+
+/// Returns a palette for `propagate_fire`, going from black through red,
+/// orange and yellow to white as the heat rises from `0` to `255`.
+pub fn fire_palette() -> [u8; PALETTE_SIZE] {
+    let mut palette = [0; PALETTE_SIZE];
+    for (heat, color) in palette.chunks_exact_mut(3).enumerate() {
+        // Each quarter of the range brings up the next component.
+        let level = (heat % 64) as u8;
+        let (red, green, blue) = match heat / 64 {
+            0 => (level, 0, 0),
+            1 => (0x3F, level, 0),
+            2 => (0x3F, 0x3F, level),
+            _ => (0x3F, 0x3F, 0x3F),
+        };
+        color.copy_from_slice(&[red, green, blue]);
+    }
+    palette
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_propagate_fire() {
+        let (width, height) = (4, 4);
+        let mut buffer = [0; 16];
+        buffer[12..].copy_from_slice(&[200, 200, 200, 200]);
+
+        propagate_fire(&mut buffer, width, height);
+        // The source row counts twice for the row right above it, and once
+        // for the row above that one through the pixel two rows down.
+        assert_eq!(&buffer[8..12], &[199, 199, 199, 199]);
+        assert_eq!(&buffer[4..8], &[49, 49, 49, 49]);
+        assert_eq!(&buffer[..4], &[0, 0, 0, 0]);
+        assert_eq!(&buffer[12..], &[200, 200, 200, 200]);
+
+        // The heat keeps rising without ever growing hotter than the source.
+        for _ in 0..20 {
+            propagate_fire(&mut buffer, width, height);
+        }
+        assert!(buffer[..12].iter().all(|&heat| heat > 0 && heat < 200));
+        assert!(buffer[0] < buffer[4] && buffer[4] < buffer[8]);
+    }
+
+    #[test]
+    fn test_fire_palette() {
+        let palette = fire_palette();
+        let color = |heat: usize| &palette[heat * 3..heat * 3 + 3];
+        assert_eq!(color(0), &[0x00, 0x00, 0x00]);
+        assert_eq!(color(63), &[0x3F, 0x00, 0x00]);
+        assert_eq!(color(96), &[0x3F, 0x20, 0x00]);
+        assert_eq!(color(191), &[0x3F, 0x3F, 0x3F]);
+        assert_eq!(color(255), &[0x3F, 0x3F, 0x3F]);
+        assert!(palette.iter().all(|&component| component <= 0x3F));
+    }
+}
@@ -1,5 +1,7 @@
 //! Common font structures used in vga programming.
 
+use font8x8::UnicodeFonts;
+
 /// Represents a font to be used for text mode.
 pub struct VgaFont {
     /// Represents the number of characters contained in the font.
@@ -10,6 +12,272 @@ pub struct VgaFont {
     pub font_data: &'static [u8],
 }
 
+/// A bitmap font that can be drawn in vga graphics modes.
+///
+/// Every glyph is 8 pixels wide, with one byte per row where the most
+/// significant bit is the leftmost pixel.
+pub trait Font {
+    /// Returns the height of each glyph in rows.
+    fn height(&self) -> usize;
+
+    /// Returns the rows of the glyph for `character`. Fonts should return a
+    /// fallback glyph for characters they don't support.
+    fn glyph(&self, character: char) -> &[u8];
+}
+
+impl Font for VgaFont {
+    fn height(&self) -> usize {
+        usize::from(self.character_height)
+    }
+
+    /// Returns the glyph at the code page 437 code point of `character`,
+    /// or `Cp437::REPLACEMENT` if code page 437 doesn't have `character`.
+    fn glyph(&self, character: char) -> &[u8] {
+        let code_point = usize::from(Cp437::encode(character).unwrap_or(Cp437::REPLACEMENT));
+        let code_point = if code_point < usize::from(self.characters) {
+            code_point
+        } else {
+            0
+        };
+        let height = self.height();
+        &self.font_data[code_point * height..(code_point + 1) * height]
+    }
+}
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_HEADER_SIZE: usize = 4;
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF2_HEADER_SIZE: usize = 32;
+
+/// The error returned when a PC Screen Font can't be parsed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PsfError {
+    /// The data doesn't start with the magic number of a PSF1 or PSF2 font.
+    UnknownMagic,
+    /// The data ends before the header or the last glyph.
+    Truncated,
+    /// The header describes glyphs that are empty or don't match their size.
+    InvalidHeader,
+    /// The glyphs are wider than the 8 pixels supported by `Font`.
+    UnsupportedWidth(usize),
+}
+
+/// A PC Screen Font, as used by the Linux console, borrowed from the
+/// contents of a `.psf` file.
+///
+/// Both PSF1 and PSF2 fonts are supported, as long as their glyphs are at
+/// most 8 pixels wide. Characters are mapped to glyphs by their code page 437
+/// code point, and any Unicode table is ignored.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::colors::Color16;
+/// use vga::fonts::PsfFont;
+/// use vga::writers::{Graphics640x480x16, GraphicsWriter};
+///
+/// fn draw_greeting(psf_data: &[u8]) {
+///     let font = PsfFont::parse(psf_data).unwrap();
+///     let mode = Graphics640x480x16::new();
+///     mode.set_mode();
+///     mode.draw_string_with_font(0, 0, "Hello!", Color16::White, &font);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PsfFont<'a> {
+    glyphs: &'a [u8],
+    glyph_count: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a> PsfFont<'a> {
+    /// Parses the PSF1 or PSF2 font stored in `data`, without copying
+    /// any of the glyphs.
+    pub fn parse(data: &'a [u8]) -> Result<PsfFont<'a>, PsfError> {
+        if data.starts_with(&PSF2_MAGIC) {
+            Self::parse_psf2(data)
+        } else if data.starts_with(&PSF1_MAGIC) {
+            Self::parse_psf1(data)
+        } else {
+            Err(PsfError::UnknownMagic)
+        }
+    }
+
+    fn parse_psf1(data: &'a [u8]) -> Result<PsfFont<'a>, PsfError> {
+        if data.len() < PSF1_HEADER_SIZE {
+            return Err(PsfError::Truncated);
+        }
+        let glyph_count = if data[2] & PSF1_MODE_512 != 0 {
+            512
+        } else {
+            256
+        };
+        Self::new(
+            &data[PSF1_HEADER_SIZE..],
+            glyph_count,
+            8,
+            usize::from(data[3]),
+        )
+    }
+
+    fn parse_psf2(data: &'a [u8]) -> Result<PsfFont<'a>, PsfError> {
+        if data.len() < PSF2_HEADER_SIZE {
+            return Err(PsfError::Truncated);
+        }
+        let field = |index: usize| {
+            let offset = 8 + index * 4;
+            let bytes = [
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ];
+            u32::from_le_bytes(bytes) as usize
+        };
+        let (header_size, glyph_count, glyph_size) = (field(0), field(2), field(3));
+        let (height, width) = (field(4), field(5));
+        if width > 8 {
+            return Err(PsfError::UnsupportedWidth(width));
+        }
+        if width == 0 || glyph_size != height || header_size < PSF2_HEADER_SIZE {
+            return Err(PsfError::InvalidHeader);
+        }
+        let glyphs = data.get(header_size..).ok_or(PsfError::Truncated)?;
+        Self::new(glyphs, glyph_count, width, height)
+    }
+
+    fn new(
+        glyphs: &'a [u8],
+        glyph_count: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PsfFont<'a>, PsfError> {
+        if glyph_count == 0 || height == 0 {
+            return Err(PsfError::InvalidHeader);
+        }
+        let size = glyph_count
+            .checked_mul(height)
+            .ok_or(PsfError::InvalidHeader)?;
+        let glyphs = glyphs.get(..size).ok_or(PsfError::Truncated)?;
+        Ok(PsfFont {
+            glyphs,
+            glyph_count,
+            width,
+            height,
+        })
+    }
+
+    /// Returns the number of glyphs in the font.
+    pub fn glyph_count(&self) -> usize {
+        self.glyph_count
+    }
+
+    /// Returns the width of each glyph in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl Font for PsfFont<'_> {
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the glyph at the code page 437 code point of `character`,
+    /// or `Cp437::REPLACEMENT` if code page 437 doesn't have `character`.
+    fn glyph(&self, character: char) -> &[u8] {
+        let index = usize::from(Cp437::encode(character).unwrap_or(Cp437::REPLACEMENT));
+        let index = if index < self.glyph_count { index } else { 0 };
+        &self.glyphs[index * self.height..(index + 1) * self.height]
+    }
+}
+
+/// The character shown for each code point of code page 437.
+const CP437_CHARACTERS: [char; 256] = [
+    '\0', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕',
+    '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼', ' ', '!', '"', '#', '$', '%',
+    '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', '0', '1', '2', '3', '4', '5', '6', '7', '8',
+    '9', ':', ';', '<', '=', '>', '?', '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K',
+    'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^',
+    '_', '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q',
+    'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂', 'Ç', 'ü', 'é', 'â', 'ä',
+    'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù',
+    'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬',
+    '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜',
+    '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', '╨',
+    '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', 'α', 'ß', 'Γ', 'π',
+    'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±', '≥', '≤', '⌠', '⌡', '÷',
+    '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// The mapping between `char`s and IBM PC code page 437, the character set of
+/// the vga fonts, which adds symbols, accented letters and box-drawing
+/// characters to ascii.
+///
+/// # Examples
+///
+/// ```
+/// use vga::fonts::Cp437;
+///
+/// assert_eq!(Cp437::encode('╔'), Some(0xC9));
+/// assert_eq!(Cp437::encode('€'), None);
+/// assert_eq!(Cp437::decode(0x82), 'é');
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Cp437;
+
+impl Cp437 {
+    /// The code point used for characters code page 437 doesn't have,
+    /// which is the full block `'█'`.
+    pub const REPLACEMENT: u8 = 0xDB;
+
+    /// Returns the code point of `character`, or `None` if code page 437
+    /// doesn't have it.
+    ///
+    /// Ascii characters, including the control characters, map to
+    /// themselves, while the symbols drawn for the control characters map
+    /// to the same code points. The greek letters beta and mu also map to
+    /// the look-alike `'ß'` and `'µ'`.
+    pub fn encode(character: char) -> Option<u8> {
+        let character = match character {
+            '\0'..='\x7F' => return Some(character as u8),
+            '\u{3B2}' => 'ß',
+            '\u{3BC}' => 'µ',
+            character => character,
+        };
+        CP437_CHARACTERS[0x01..]
+            .iter()
+            .position(|&c| c == character)
+            .map(|index| index as u8 + 1)
+    }
+
+    /// Returns the character shown for `code_point`, where the control
+    /// characters are shown as symbols such as `'☺'` for `0x01`.
+    pub fn decode(code_point: u8) -> char {
+        CP437_CHARACTERS[usize::from(code_point)]
+    }
+}
+
+/// Returns the rows of the 8x8 glyph used by the graphics writers for
+/// `character`, with the least significant bit as the leftmost pixel.
+///
+/// Ascii characters come from `font8x8`, while the rest of code page 437 comes
+/// from `TEXT_8X8_FONT`, so box-drawing characters line up with each other.
+pub(crate) fn basic_glyph(character: char) -> [u8; 8] {
+    if let Some(glyph) = font8x8::BASIC_FONTS.get(character) {
+        return glyph;
+    }
+    let mut glyph = [0; 8];
+    for (row, byte) in glyph.iter_mut().zip(TEXT_8X8_FONT.glyph(character)) {
+        *row = byte.reverse_bits();
+    }
+    glyph
+}
+
 /// Standard 8x8 character font.
 pub const TEXT_8X8_FONT: VgaFont = VgaFont {
     characters: 256,
@@ -438,3 +706,124 @@ pub const TEXT_8X16_FONT: VgaFont = VgaFont {
         0x00,
     ],
 };
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vga_font_glyph() {
+        assert_eq!(TEXT_8X8_FONT.height(), 8);
+        assert_eq!(
+            TEXT_8X8_FONT.glyph('A'),
+            &TEXT_8X8_FONT.font_data[0x41 * 8..0x42 * 8]
+        );
+        assert_eq!(TEXT_8X16_FONT.height(), 16);
+        assert_eq!(TEXT_8X16_FONT.glyph('A').len(), 16);
+        assert_eq!(
+            TEXT_8X8_FONT.glyph('é'),
+            &TEXT_8X8_FONT.font_data[0x82 * 8..0x83 * 8]
+        );
+        assert_eq!(
+            TEXT_8X8_FONT.glyph('€'),
+            &TEXT_8X8_FONT.font_data[0xDB * 8..0xDC * 8]
+        );
+    }
+
+    #[test]
+    fn test_cp437() {
+        for code_point in 0x01..=0xFF {
+            assert_eq!(Cp437::encode(Cp437::decode(code_point)), Some(code_point));
+        }
+        assert_eq!(Cp437::encode('\0'), Some(0x00));
+        assert_eq!(Cp437::encode('\n'), Some(0x0A));
+        assert_eq!(Cp437::encode('◙'), Some(0x0A));
+        assert_eq!(Cp437::encode('═'), Some(0xCD));
+        assert_eq!(Cp437::encode('\u{3B2}'), Some(0xE1));
+        assert_eq!(Cp437::encode('€'), None);
+        assert_eq!(Cp437::decode(0x41), 'A');
+        assert_eq!(Cp437::decode(0xFF), '\u{A0}');
+    }
+
+    #[test]
+    fn test_basic_glyph() {
+        assert_eq!(basic_glyph('A'), font8x8::BASIC_FONTS.get('A').unwrap());
+        // The top left corner is drawn from the rightmost pixel of each row.
+        let corner = &TEXT_8X8_FONT.font_data[0xC9 * 8..0xCA * 8];
+        assert!(basic_glyph('╔')
+            .iter()
+            .zip(corner)
+            .all(|(row, byte)| *row == byte.reverse_bits()));
+        assert_eq!(basic_glyph('€'), [0xFF; 8]);
+    }
+
+    fn psf1(mode: u8, height: u8, glyphs: usize) -> [u8; 4 + 512 * 2] {
+        let mut data = [0; 4 + 512 * 2];
+        data[..4].copy_from_slice(&[0x36, 0x04, mode, height]);
+        for (i, byte) in data[4..4 + glyphs * usize::from(height)]
+            .iter_mut()
+            .enumerate()
+        {
+            *byte = (i / usize::from(height)) as u8;
+        }
+        data
+    }
+
+    fn psf2(width: u32, height: u32, glyph_size: u32) -> [u8; 32 + 256 * 2] {
+        let mut data = [0; 32 + 256 * 2];
+        let fields = [0, 32, 0, 256, glyph_size, height, width];
+        data[..4].copy_from_slice(&PSF2_MAGIC);
+        for (i, field) in fields.iter().enumerate() {
+            data[4 + i * 4..8 + i * 4].copy_from_slice(&u32::to_le_bytes(*field));
+        }
+        for (i, byte) in data[32..].iter_mut().enumerate() {
+            *byte = !(i / 2) as u8;
+        }
+        data
+    }
+
+    #[test]
+    fn test_psf1_font() {
+        let data = psf1(0, 2, 256);
+        let font = PsfFont::parse(&data).unwrap();
+        assert_eq!(font.glyph_count(), 256);
+        assert_eq!((font.width(), font.height()), (8, 2));
+        assert_eq!(font.glyph('A'), &[0x41, 0x41]);
+        assert_eq!(font.glyph('ÿ'), &[0x98, 0x98]);
+        assert_eq!(font.glyph('€'), &[0xDB, 0xDB]);
+
+        let data = psf1(PSF1_MODE_512, 2, 512);
+        let font = PsfFont::parse(&data).unwrap();
+        assert_eq!(font.glyph_count(), 512);
+        assert_eq!(font.glyph('\u{100}'), &[0xDB, 0xDB]);
+
+        let data = psf1(PSF1_MODE_512, 3, 256);
+        assert_eq!(PsfFont::parse(&data), Err(PsfError::Truncated));
+    }
+
+    #[test]
+    fn test_psf2_font() {
+        let data = psf2(6, 2, 2);
+        let font = PsfFont::parse(&data).unwrap();
+        assert_eq!(font.glyph_count(), 256);
+        assert_eq!((font.width(), font.height()), (6, 2));
+        assert_eq!(font.glyph('\x01'), &[0xFE, 0xFE]);
+
+        let data = psf2(12, 2, 4);
+        assert_eq!(PsfFont::parse(&data), Err(PsfError::UnsupportedWidth(12)));
+        let data = psf2(8, 2, 3);
+        assert_eq!(PsfFont::parse(&data), Err(PsfError::InvalidHeader));
+        let data = psf2(8, 3, 3);
+        assert_eq!(PsfFont::parse(&data), Err(PsfError::Truncated));
+        assert_eq!(PsfFont::parse(&data[..20]), Err(PsfError::Truncated));
+        assert_eq!(PsfFont::parse(&[0; 40]), Err(PsfError::UnknownMagic));
+    }
+
+    #[test]
+    fn test_vga_font_size() {
+        for font in [&TEXT_8X8_FONT, &TEXT_8X16_FONT].iter() {
+            let size = usize::from(font.characters) * usize::from(font.character_height);
+            assert_eq!(font.font_data.len(), size);
+        }
+    }
+}
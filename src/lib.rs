@@ -9,7 +9,11 @@
 
 pub mod colors;
 pub mod configurations;
+pub mod dither;
+#[cfg(feature = "embedded-graphics")]
+mod draw_target;
 pub mod drawing;
+pub mod effects;
 pub mod fonts;
 pub mod registers;
 pub mod vga;
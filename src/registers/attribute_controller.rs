@@ -80,9 +80,7 @@ impl AttributeControllerRegisters {
     /// Reads the current value of the attribute controller, as specified
     /// by `emulation_mode` and `index`.
     pub fn read(&mut self, emulation_mode: EmulationMode, index: AttributeControllerIndex) -> u8 {
-        self.toggle_index(emulation_mode);
-        self.set_index(index);
-        unsafe { self.arx_data.read() }
+        self.read_raw(emulation_mode, u8::from(index))
     }
 
     /// Writes the `value` to the attribute controller, as specified
@@ -93,6 +91,28 @@ impl AttributeControllerRegisters {
         index: AttributeControllerIndex,
         value: u8,
     ) {
+        self.write_raw(emulation_mode, u8::from(index), value);
+    }
+
+    /// Reads the current value of the attribute controller at the raw `index`,
+    /// resetting the index/data flip-flop first.
+    ///
+    /// `index` is written to the address register as is, so bit 5 (`0x20`) is
+    /// the palette address source bit, and the screen stays blank until it's
+    /// written set or `unblank_screen` is called.
+    pub fn read_raw(&mut self, emulation_mode: EmulationMode, index: u8) -> u8 {
+        self.toggle_index(emulation_mode);
+        self.set_index(index);
+        unsafe { self.arx_data.read() }
+    }
+
+    /// Writes the `value` to the attribute controller at the raw `index`,
+    /// resetting the index/data flip-flop first. Bit 5 of `index` is the
+    /// palette address source bit, like for `read_raw`.
+    ///
+    /// A wrong value can garble the display, so prefer `write` whenever the
+    /// register has an `AttributeControllerIndex`.
+    pub fn write_raw(&mut self, emulation_mode: EmulationMode, index: u8, value: u8) {
         self.toggle_index(emulation_mode);
         self.set_index(index);
         unsafe {
@@ -132,9 +152,9 @@ impl AttributeControllerRegisters {
         }
     }
 
-    fn set_index(&mut self, index: AttributeControllerIndex) {
+    fn set_index(&mut self, index: u8) {
         unsafe {
-            self.arx_index.write(u8::from(index));
+            self.arx_index.write(index);
         }
     }
 
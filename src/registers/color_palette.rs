@@ -2,6 +2,7 @@ use super::{
     COLOR_PALETTE_DATA_ADDRESS, COLOR_PALETTE_INDEX_READ_ADDRESS,
     COLOR_PALETTE_INDEX_WRITE_ADDRESSS, PALETTE_SIZE,
 };
+use crate::colors::{grayscale, Palette, PaletteColor};
 use x86_64::instructions::port::Port;
 
 /// Represents the color palette registers on vga hardware.
@@ -34,6 +35,59 @@ impl ColorPaletteRegisters {
         }
     }
 
+    /// Loads a 256 color palette, as specified by `palette`, after mapping each
+    /// component through the `gamma` lookup table, such as `colors::GAMMA_2_2`.
+    ///
+    /// Components are masked to the range `0x00 -> 0x3F` before the lookup.
+    pub fn load_palette_gamma(&mut self, palette: &[u8; PALETTE_SIZE], gamma: &[u8; 64]) {
+        unsafe {
+            self.index_write_port.write(0);
+        }
+        for i in palette.iter() {
+            unsafe {
+                self.data_port.write(gamma[usize::from(*i & 0x3F)]);
+            }
+        }
+    }
+
+    /// Sets the color at `index` of the 256 color palette to the given
+    /// `red`, `green` and `blue` components.
+    ///
+    /// The dac only has 6 bits of resolution per component, so each
+    /// component is masked to the range `0x00 -> 0x3F`.
+    pub fn set_color(&mut self, index: u8, red: u8, green: u8, blue: u8) {
+        unsafe {
+            self.index_write_port.write(index);
+            self.data_port.write(red & 0x3F);
+            self.data_port.write(green & 0x3F);
+            self.data_port.write(blue & 0x3F);
+        }
+    }
+
+    /// Sets the color at `index` of the 256 color palette to `color`.
+    pub fn set_palette_color(&mut self, index: u8, color: PaletteColor) {
+        unsafe {
+            self.index_write_port.write(index);
+            self.data_port.write(color.red.value());
+            self.data_port.write(color.green.value());
+            self.data_port.write(color.blue.value());
+        }
+    }
+
+    /// Loads a 256 color palette, as specified by `palette`.
+    ///
+    /// Components are masked to the range `0x00 -> 0x3F`, like `set_color` does.
+    pub fn load_palette_colors(&mut self, palette: &Palette) {
+        unsafe {
+            self.index_write_port.write(0);
+        }
+        for i in palette.as_bytes().iter() {
+            unsafe {
+                self.data_port.write(*i & 0x3F);
+            }
+        }
+    }
+
     /// Reads the current 256 color palette into `palette`, with every 3
     /// bytes representing a color.
     pub fn read_palette(&mut self, palette: &mut [u8; PALETTE_SIZE]) {
@@ -46,4 +100,64 @@ impl ColorPaletteRegisters {
             }
         }
     }
+
+    /// Replaces every color of the current 256 color palette with its
+    /// luminance, desaturating the whole screen without redrawing it.
+    ///
+    /// The original colors are lost, read them with `read_palette` first
+    /// to restore them later.
+    pub fn set_grayscale_mode(&mut self) {
+        let mut palette = [0; PALETTE_SIZE];
+        self.read_palette(&mut palette);
+        self.load_palette(&grayscale(&palette));
+    }
+
+    /// Cyclically shifts the `count` palette entries starting at `start` up by
+    /// one position, moving the last entry of the range to `start`. Calling this
+    /// once per frame animates anything drawn with those colors without
+    /// redrawing any pixels.
+    ///
+    /// Only the entries in the range are read and written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start + count` is greater than 256.
+    pub fn rotate_range(&mut self, start: u8, count: u8) {
+        let start = usize::from(start);
+        let count = usize::from(count);
+        assert!(start + count <= 256, "palette range is out of bounds");
+        if count < 2 {
+            return;
+        }
+
+        let mut colors = [0; PALETTE_SIZE];
+        let colors = &mut colors[..count * 3];
+        unsafe {
+            self.index_read_port.write(start as u8);
+            for byte in colors.iter_mut() {
+                *byte = self.data_port.read();
+            }
+        }
+
+        colors.rotate_right(3);
+        unsafe {
+            self.index_write_port.write(start as u8);
+            for byte in colors.iter() {
+                self.data_port.write(*byte);
+            }
+        }
+    }
+
+    /// Reads the color at `index` of the current 256 color palette,
+    /// returned as its `(red, green, blue)` components.
+    pub fn read_color(&mut self, index: u8) -> (u8, u8, u8) {
+        unsafe {
+            self.index_read_port.write(index);
+            (
+                self.data_port.read(),
+                self.data_port.read(),
+                self.data_port.read(),
+            )
+        }
+    }
 }
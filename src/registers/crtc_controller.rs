@@ -92,22 +92,39 @@ impl CrtcControllerRegisters {
     /// Reads the current value from the crtc controller, as specified
     /// by `emulation_mode` and `index`.
     pub fn read(&mut self, emulation_mode: EmulationMode, index: CrtcControllerIndex) -> u8 {
-        self.set_index(emulation_mode, index);
-        unsafe { self.get_data_port(emulation_mode).read() }
+        self.read_raw(emulation_mode, u8::from(index))
     }
 
     /// Writes the `value` to the crtc_controller, as specified
     /// by `emulation_mode` and `index`.
     pub fn write(&mut self, emulation_mode: EmulationMode, index: CrtcControllerIndex, value: u8) {
+        self.write_raw(emulation_mode, u8::from(index), value);
+    }
+
+    /// Reads the current value from the crtc controller at the raw `index`.
+    ///
+    /// Unlike `read`, any index can be given, including vendor specific
+    /// registers that have no `CrtcControllerIndex`.
+    pub fn read_raw(&mut self, emulation_mode: EmulationMode, index: u8) -> u8 {
+        self.set_index(emulation_mode, index);
+        unsafe { self.get_data_port(emulation_mode).read() }
+    }
+
+    /// Writes the `value` to the crtc controller at the raw `index`.
+    ///
+    /// This bypasses the typed indexes, and a wrong value can garble the
+    /// display or produce timings the monitor can't sync to, so prefer
+    /// `write` whenever the register has a `CrtcControllerIndex`.
+    pub fn write_raw(&mut self, emulation_mode: EmulationMode, index: u8, value: u8) {
         self.set_index(emulation_mode, index);
         unsafe {
             self.get_data_port(emulation_mode).write(value);
         }
     }
 
-    fn set_index(&mut self, emulation_mode: EmulationMode, index: CrtcControllerIndex) {
+    fn set_index(&mut self, emulation_mode: EmulationMode, index: u8) {
         unsafe {
-            self.get_index_port(emulation_mode).write(u8::from(index));
+            self.get_index_port(emulation_mode).write(index);
         }
     }
 
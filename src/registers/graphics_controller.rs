@@ -3,7 +3,6 @@ use core::convert::TryFrom;
 use x86_64::instructions::port::Port;
 
 /// Represents a plane for the `GraphicsControllerIndex::ReadPlaneSelect` register.
-#[allow(dead_code)]
 #[derive(Debug, Copy, Clone)]
 #[repr(u8)]
 pub enum ReadPlane {
@@ -130,6 +129,39 @@ impl From<WriteMode> for u8 {
     }
 }
 
+/// Represents the logical operation applied between the data being written
+/// and the contents of the read latches, as selected by the function select
+/// bits of the `GraphicsControllerIndex::DataRotate` register.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RasterOp {
+    /// The data is written unmodified.
+    Replace = 0x0,
+    /// The data is ANDed with the read latches.
+    And = 0x1,
+    /// The data is ORed with the read latches.
+    Or = 0x2,
+    /// The data is XORed with the read latches.
+    Xor = 0x3,
+}
+
+impl From<RasterOp> for u8 {
+    fn from(value: RasterOp) -> u8 {
+        value as u8
+    }
+}
+
+impl From<u8> for RasterOp {
+    fn from(value: u8) -> RasterOp {
+        match value & 0x3 {
+            0x0 => RasterOp::Replace,
+            0x1 => RasterOp::And,
+            0x2 => RasterOp::Or,
+            _ => RasterOp::Xor,
+        }
+    }
+}
+
 /// Represents the graphics controller registers on vga hardware.
 #[derive(Debug)]
 pub struct GraphicsControllerRegisters {
@@ -148,13 +180,30 @@ impl GraphicsControllerRegisters {
     /// Reads the current value from the graphics controller, as specified
     /// by `index`.
     pub fn read(&mut self, index: GraphicsControllerIndex) -> u8 {
-        self.set_index(index);
-        unsafe { self.grx_data.read() }
+        self.read_raw(u8::from(index))
     }
 
     /// Writes the `value` to the graphics controller, as specified
     /// by `index.
     pub fn write(&mut self, index: GraphicsControllerIndex, value: u8) {
+        self.write_raw(u8::from(index), value);
+    }
+
+    /// Reads the current value from the graphics controller at the raw `index`.
+    ///
+    /// Unlike `read`, any index can be given, including vendor specific
+    /// registers that have no `GraphicsControllerIndex`.
+    pub fn read_raw(&mut self, index: u8) -> u8 {
+        self.set_index(index);
+        unsafe { self.grx_data.read() }
+    }
+
+    /// Writes the `value` to the graphics controller at the raw `index`.
+    ///
+    /// This bypasses the typed indexes, and a wrong value can garble the
+    /// display, so prefer `write` whenever the register has a
+    /// `GraphicsControllerIndex`.
+    pub fn write_raw(&mut self, index: u8, value: u8) {
         self.set_index(index);
         unsafe {
             self.grx_data.write(value);
@@ -196,15 +245,32 @@ impl GraphicsControllerRegisters {
         );
     }
 
+    /// Returns the `RasterOp` currently applied to writes.
+    pub fn get_raster_op(&mut self) -> RasterOp {
+        RasterOp::from(self.read(GraphicsControllerIndex::DataRotate) >> 3)
+    }
+
+    /// Sets the logical operation applied to writes, as specified by `raster_op`.
+    /// Every operation except `RasterOp::Replace` combines the written data with
+    /// the read latches, so the latches need to be loaded by reading the
+    /// destination byte first.
+    pub fn set_raster_op(&mut self, raster_op: RasterOp) {
+        let original_value = self.read(GraphicsControllerIndex::DataRotate) & 0xE7;
+        self.write(
+            GraphicsControllerIndex::DataRotate,
+            original_value | u8::from(raster_op) << 3,
+        );
+    }
+
     /// Sets which bits are effected by certain operations, as specified
     /// by `bit_mask`.
     pub fn set_bit_mask(&mut self, bit_mask: u8) {
         self.write(GraphicsControllerIndex::BitMask, bit_mask);
     }
 
-    fn set_index(&mut self, index: GraphicsControllerIndex) {
+    fn set_index(&mut self, index: u8) {
         unsafe {
-            self.grx_index.write(u8::from(index));
+            self.grx_index.write(index);
         }
     }
 }
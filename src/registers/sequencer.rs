@@ -21,6 +21,30 @@ bitflags! {
     }
 }
 
+impl PlaneMask {
+    /// Returns a `PlaneMask` with each of the given `planes` enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the `planes` isn't between 0-3.
+    pub fn from_planes(planes: &[u8]) -> PlaneMask {
+        planes.iter().fold(PlaneMask::NONE, |mask, &plane| {
+            mask | PlaneMask::try_from(plane).unwrap()
+        })
+    }
+
+    /// Returns `true` if `plane` is enabled in the mask.
+    pub fn contains_plane(self, plane: u8) -> bool {
+        PlaneMask::try_from(plane).is_ok_and(|mask| self.contains(mask))
+    }
+
+    /// Returns an iterator over the indices of the enabled planes,
+    /// from plane 0 to plane 3.
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (0..4).filter(move |&plane| self.contains_plane(plane))
+    }
+}
+
 impl TryFrom<u8> for PlaneMask {
     type Error = &'static str;
 
@@ -82,12 +106,28 @@ impl SequencerRegisters {
 
     /// Reads the current value from the sequencer, as specified by `index`.
     pub fn read(&mut self, index: SequencerIndex) -> u8 {
-        self.set_index(index);
-        unsafe { self.srx_data.read() }
+        self.read_raw(u8::from(index))
     }
 
     /// Writes the `value` to the sequencer, as specified by `index`.
     pub fn write(&mut self, index: SequencerIndex, value: u8) {
+        self.write_raw(u8::from(index), value);
+    }
+
+    /// Reads the current value from the sequencer at the raw `index`.
+    ///
+    /// Unlike `read`, any index can be given, including vendor specific
+    /// registers that have no `SequencerIndex`.
+    pub fn read_raw(&mut self, index: u8) -> u8 {
+        self.set_index(index);
+        unsafe { self.srx_data.read() }
+    }
+
+    /// Writes the `value` to the sequencer at the raw `index`.
+    ///
+    /// This bypasses the typed indexes, and a wrong value can garble the
+    /// display, so prefer `write` whenever the register has a `SequencerIndex`.
+    pub fn write_raw(&mut self, index: u8, value: u8) {
         self.set_index(index);
         unsafe {
             self.srx_data.write(value);
@@ -103,9 +143,35 @@ impl SequencerRegisters {
         );
     }
 
-    fn set_index(&mut self, index: SequencerIndex) {
+    fn set_index(&mut self, index: u8) {
         unsafe {
-            self.srx_index.write(u8::from(index));
+            self.srx_index.write(index);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_plane_mask_from_planes() {
+        assert_eq!(PlaneMask::from_planes(&[]), PlaneMask::NONE);
+        assert_eq!(
+            PlaneMask::from_planes(&[0, 2]),
+            PlaneMask::PLANE0 | PlaneMask::PLANE2
+        );
+        assert_eq!(PlaneMask::from_planes(&[3, 1, 0, 2]), PlaneMask::ALL_PLANES);
+    }
+
+    #[test]
+    fn test_plane_mask_planes() {
+        let mask = PlaneMask::PLANE1 | PlaneMask::PLANE3;
+        assert!(mask.contains_plane(1));
+        assert!(!mask.contains_plane(2));
+        assert!(!mask.contains_plane(4));
+        assert!(mask.iter().eq([1, 3]));
+        assert!(PlaneMask::NONE.iter().eq([]));
+        assert!(PlaneMask::ALL_PLANES.iter().eq(0..4));
+    }
+}
@@ -1,17 +1,21 @@
 //! Provides access to the vga graphics card.
 
 use super::{
+    colors::{Color16, TextModeColor, PALETTE_SIZE},
     configurations::{
-        VgaConfiguration, MODE_320X200X256_CONFIGURATION, MODE_320X240X256_CONFIGURATION,
-        MODE_40X25_CONFIGURATION, MODE_40X50_CONFIGURATION, MODE_640X480X16_CONFIGURATION,
-        MODE_80X25_CONFIGURATION,
+        VgaConfiguration, MODE_320X200X16_CONFIGURATION, MODE_320X200X256_CONFIGURATION,
+        MODE_320X200X4_CONFIGURATION, MODE_320X240X256_CONFIGURATION, MODE_40X25_CONFIGURATION,
+        MODE_40X50_CONFIGURATION, MODE_640X350X16_CONFIGURATION, MODE_640X480X16_CONFIGURATION,
+        MODE_80X25_CONFIGURATION, MODE_80X50_CONFIGURATION,
     },
-    fonts::VgaFont,
+    fonts::{VgaFont, TEXT_8X16_FONT, TEXT_8X8_FONT},
     registers::{
-        AttributeControllerRegisters, ColorPaletteRegisters, CrtcControllerIndex,
-        CrtcControllerRegisters, EmulationMode, GeneralRegisters, GraphicsControllerIndex,
-        GraphicsControllerRegisters, PlaneMask, SequencerIndex, SequencerRegisters,
+        AttributeControllerIndex, AttributeControllerRegisters, ColorPaletteRegisters,
+        CrtcControllerIndex, CrtcControllerRegisters, EmulationMode, GeneralRegisters,
+        GraphicsControllerIndex, GraphicsControllerRegisters, PlaneMask, SequencerIndex,
+        SequencerRegisters,
     },
+    writers::ScreenCharacter,
 };
 use conquer_once::spin::Lazy;
 use spinning_top::Spinlock;
@@ -19,9 +23,34 @@ use spinning_top::Spinlock;
 /// Provides mutable access to the vga graphics card.
 pub static VGA: Lazy<Spinlock<Vga>> = Lazy::new(|| Spinlock::new(Vga::new()));
 
+/// Locks `VGA` and runs `f` with the `Vga` and a pointer to the start of the
+/// current frame buffer, releasing the lock once `f` returns.
+///
+/// This allows custom sequences of register and frame buffer accesses to run
+/// without another user of `VGA` getting in between. The writers lock `VGA`
+/// themselves, so calling them from within `f` deadlocks.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vga::registers::PlaneMask;
+/// use vga::vga::with_locked_vga;
+///
+/// with_locked_vga(|vga, frame_buffer| {
+///     vga.sequencer_registers.set_plane_mask(PlaneMask::PLANE2);
+///     unsafe { frame_buffer.write_volatile(0xFF) };
+///     vga.sequencer_registers.set_plane_mask(PlaneMask::ALL_PLANES);
+/// });
+/// ```
+pub fn with_locked_vga<R>(f: impl FnOnce(&mut Vga, *mut u8) -> R) -> R {
+    let mut vga = VGA.lock();
+    let frame_buffer = vga.get_frame_buffer().as_ptr();
+    f(&mut vga, frame_buffer)
+}
+
 /// Represents the starting address of the frame buffer for
 /// various video modes.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum FrameBuffer {
     /// The starting address for graphics modes.
@@ -32,6 +61,28 @@ pub enum FrameBuffer {
     MdaMode = 0xb0000,
 }
 
+impl FrameBuffer {
+    /// Returns the start of the frame buffer as a pointer.
+    pub const fn as_ptr(self) -> *mut u8 {
+        self as usize as *mut u8
+    }
+
+    /// Returns the size of the frame buffer window in bytes, which is
+    /// 64 KiB for graphics modes and 32 KiB for text modes.
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(self) -> usize {
+        match self {
+            FrameBuffer::GraphicsMode => 0x10000,
+            FrameBuffer::CgaMode | FrameBuffer::MdaMode => 0x8000,
+        }
+    }
+
+    /// Returns the real mode segment of the frame buffer, such as `0xA000`.
+    pub const fn segment(self) -> u16 {
+        (self as u32 >> 4) as u16
+    }
+}
+
 impl From<u8> for FrameBuffer {
     fn from(value: u8) -> FrameBuffer {
         match value {
@@ -50,7 +101,7 @@ impl From<FrameBuffer> for u32 {
 }
 
 /// Represents a specified vga video mode.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VideoMode {
     /// Represents text mode 40x25.
     Mode40x25,
@@ -58,14 +109,70 @@ pub enum VideoMode {
     Mode40x50,
     /// Represents text mode 80x25.
     Mode80x25,
+    /// Represents text mode 80x50.
+    Mode80x50,
+    /// Represents graphics mode 320x200x4.
+    Mode320x200x4,
+    /// Represents graphics mode 320x200x16.
+    Mode320x200x16,
     /// Represents graphics mode 320x200x256.
     Mode320x200x256,
     /// Represents graphics mode 320x240x256.
     Mode320x240x256,
+    /// Represents graphics mode 640x350x16.
+    Mode640x350x16,
     /// Represents graphics mode 640x480x16.
     Mode640x480x16,
 }
 
+impl VideoMode {
+    /// Returns `true` if this is one of the text modes.
+    pub fn is_text_mode(self) -> bool {
+        match self {
+            VideoMode::Mode40x25
+            | VideoMode::Mode40x50
+            | VideoMode::Mode80x25
+            | VideoMode::Mode80x50 => true,
+            VideoMode::Mode320x200x4
+            | VideoMode::Mode320x200x16
+            | VideoMode::Mode320x200x256
+            | VideoMode::Mode320x240x256
+            | VideoMode::Mode640x350x16
+            | VideoMode::Mode640x480x16 => false,
+        }
+    }
+
+    /// Returns `true` if this is one of the graphics modes.
+    pub fn is_graphics_mode(self) -> bool {
+        !self.is_text_mode()
+    }
+}
+
+/// Every configuration programs the same set of registers, so any
+/// of them can be used to look up which registers make up a `VgaState`.
+const STATE_REGISTERS: &VgaConfiguration = &MODE_80X25_CONFIGURATION;
+
+/// A snapshot of the vga registers and palette, as returned by
+/// `Vga::save_state`, that can be stored anywhere and later passed
+/// to `Vga::restore_state`.
+#[derive(Debug, Clone, Copy)]
+pub struct VgaState {
+    /// Represents the value of the miscellaneous output register.
+    pub miscellaneous_output: u8,
+    /// Represents the values of the sequencer registers.
+    pub sequencer_registers: [(SequencerIndex, u8); 5],
+    /// Represents the values of the crtc controller registers.
+    pub crtc_controller_registers: [(CrtcControllerIndex, u8); 25],
+    /// Represents the values of the graphics controller registers.
+    pub graphics_controller_registers: [(GraphicsControllerIndex, u8); 9],
+    /// Represents the values of the attribute controller registers.
+    pub attribute_controller_registers: [(AttributeControllerIndex, u8); 21],
+    /// Represents the colors of the dac palette.
+    pub palette: [u8; PALETTE_SIZE],
+    /// Represents the most recent video mode at the time the state was saved.
+    pub video_mode: Option<VideoMode>,
+}
+
 /// Represents a vga graphics card with it's common registers,
 /// as well as the most recent video mode.
 pub struct Vga {
@@ -98,17 +205,46 @@ impl Vga {
     }
 
     /// Sets the vga graphics card to the given `VideoMode`.
+    ///
+    /// Graphics modes overwrite the text mode font stored in plane 2, so
+    /// switching to a text mode also reloads its standard font.
     pub fn set_video_mode(&mut self, video_mode: VideoMode) {
         match video_mode {
             VideoMode::Mode40x25 => self.set_video_mode_40x25(),
             VideoMode::Mode40x50 => self.set_video_mode_40x50(),
             VideoMode::Mode80x25 => self.set_video_mode_80x25(),
+            VideoMode::Mode80x50 => self.set_video_mode_80x50(),
+            VideoMode::Mode320x200x4 => self.set_video_mode_320x200x4(),
+            VideoMode::Mode320x200x16 => self.set_video_mode_320x200x16(),
             VideoMode::Mode320x200x256 => self.set_video_mode_320x200x256(),
             VideoMode::Mode320x240x256 => self.set_video_mode_320x240x256(),
+            VideoMode::Mode640x350x16 => self.set_video_mode_640x350x16(),
             VideoMode::Mode640x480x16 => self.set_video_mode_640x480x16(),
         }
     }
 
+    /// Programs every register in `configuration`, for modes that have no
+    /// `VideoMode`, such as tweaked modes built from published register tables.
+    /// The frame buffer it uses is given by `VgaConfiguration::frame_buffer`.
+    ///
+    /// No font is loaded, and `Vga::current_video_mode` returns `None`
+    /// afterwards since the crate doesn't know what the mode looks like.
+    ///
+    /// # Examples
+    ///
+    /// Switching to mode 640x480x16 through its configuration:
+    ///
+    /// ```no_run
+    /// use vga::configurations::MODE_640X480X16_CONFIGURATION;
+    /// use vga::vga::VGA;
+    ///
+    /// VGA.lock().set_custom_mode(&MODE_640X480X16_CONFIGURATION);
+    /// ```
+    pub fn set_custom_mode(&mut self, configuration: &VgaConfiguration) {
+        self.set_registers(configuration);
+        self.most_recent_video_mode = None;
+    }
+
     /// Gets the `FrameBuffer` address as specified by the
     /// `Miscellaneous Output Register`.
     pub fn get_frame_buffer(&mut self) -> FrameBuffer {
@@ -125,13 +261,388 @@ impl Vga {
         self.most_recent_video_mode
     }
 
+    /// Returns the `VideoMode` the vga is currently in, as set by the last
+    /// call to `Vga::set_video_mode`.
+    ///
+    /// The registers aren't inspected, so this returns `None` if the current
+    /// mode was set before this crate was used, e.g. by the bios.
+    pub fn current_video_mode(&self) -> Option<VideoMode> {
+        self.most_recent_video_mode
+    }
+
+    /// Returns `true` if the current video mode, as returned by
+    /// `Vga::current_video_mode`, is known to be a text mode.
+    pub fn is_text_mode(&self) -> bool {
+        self.most_recent_video_mode
+            .is_some_and(VideoMode::is_text_mode)
+    }
+
+    /// Returns `true` if the current video mode, as returned by
+    /// `Vga::current_video_mode`, is known to be a graphics mode.
+    pub fn is_graphics_mode(&self) -> bool {
+        self.most_recent_video_mode
+            .is_some_and(VideoMode::is_graphics_mode)
+    }
+
     /// Returns the current `EmulationMode` as determined by the miscellaneous output register.
     pub fn get_emulation_mode(&mut self) -> EmulationMode {
         EmulationMode::from(self.general_registers.read_msr() & 0x1)
     }
 
+    /// Sets the color of the border (overscan) area around the active display.
+    ///
+    /// The attribute controller's index/data flip-flop is reset before
+    /// the write, and the screen is unblanked again afterwards since
+    /// selecting the register clears the palette address source bit.
+    pub fn set_border_color(&mut self, color: Color16) {
+        let emulation_mode = self.get_emulation_mode();
+        self.attribute_controller_registers.write(
+            emulation_mode,
+            AttributeControllerIndex::OverscanColor,
+            u8::from(color),
+        );
+        self.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+    }
+
+    /// Returns the 16 attribute controller palette registers `(0x00-0x0F)`,
+    /// where entry `i` is the dac index pixel value `i` is displayed with.
+    ///
+    /// The screen is unblanked afterwards, as for `Vga::set_attribute_palette`.
+    pub fn get_attribute_palette(&mut self) -> [u8; 16] {
+        let emulation_mode = self.get_emulation_mode();
+        let mut mapping = [0; 16];
+        for (index, entry) in mapping.iter_mut().enumerate() {
+            *entry = self
+                .attribute_controller_registers
+                .read_raw(emulation_mode, index as u8);
+        }
+        self.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+        mapping
+    }
+
+    /// Points pixel value `i` at dac index `mapping[i]` by writing the 16
+    /// attribute controller palette registers `(0x00-0x0F)`. Changing the
+    /// mapping recolors the whole screen at once without touching the frame
+    /// buffer or the dac, e.g. to cycle colors in 16 color modes.
+    ///
+    /// The palette registers can only be written while the palette address
+    /// source bit is clear, which blanks the screen, so all 16 are written
+    /// before the screen is unblanked. The index/data flip-flop is reset
+    /// before each write.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any entry of `mapping` is greater than `0x3F`, since the
+    /// palette registers are 6 bits wide.
+    pub fn set_attribute_palette(&mut self, mapping: [u8; 16]) {
+        assert!(
+            mapping.iter().all(|&entry| entry <= 0x3F),
+            "attribute palette entries must be at most 0x3F"
+        );
+        let emulation_mode = self.get_emulation_mode();
+        for (index, &entry) in mapping.iter().enumerate() {
+            self.attribute_controller_registers
+                .write_raw(emulation_mode, index as u8, entry);
+        }
+        self.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+    }
+
+    /// Turns off the display output by setting the screen off bit `(0x20)`
+    /// of the sequencer's clocking mode register. The monitor shows black,
+    /// but the frame buffer can still be read and written, so a frame can be
+    /// drawn while blanked and revealed with `Vga::unblank_screen`.
+    ///
+    /// With the display off, the vga also stops fetching from memory, which
+    /// gives the cpu faster access to the frame buffer.
+    pub fn blank_screen(&mut self) {
+        let clocking_mode = self.sequencer_registers.read(SequencerIndex::ClockingMode);
+        self.sequencer_registers
+            .write(SequencerIndex::ClockingMode, clocking_mode | 0x20);
+    }
+
+    /// Turns the display output back on after `Vga::blank_screen`.
+    pub fn unblank_screen(&mut self) {
+        let clocking_mode = self.sequencer_registers.read(SequencerIndex::ClockingMode);
+        self.sequencer_registers
+            .write(SequencerIndex::ClockingMode, clocking_mode & !0x20);
+    }
+
+    /// Splits the screen at `scanline`, below which the display starts over from
+    /// the beginning of video memory, regardless of the start address. This
+    /// keeps a status bar at the bottom still while the area above it scrolls.
+    /// Passing `None` disables the split.
+    ///
+    /// The 10 bit line compare value is spread over bits 0-7 of the line compare
+    /// register `(0x18)`, bit 4 of the overflow register `(0x07)` and bit 6 of
+    /// the maximum scan line register `(0x09)`. Disabling the split sets it to
+    /// `0x3FF`, which is never reached. `scanline` counts the scan lines sent
+    /// to the monitor, so double scanned modes count every row twice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scanline` is greater than `0x3FF`.
+    pub fn set_split_screen(&mut self, scanline: Option<u16>) {
+        let line_compare = scanline.unwrap_or(0x3FF);
+        assert!(line_compare <= 0x3FF, "scanline must be at most 0x3FF");
+
+        let emulation_mode = self.get_emulation_mode();
+        let overflow = self
+            .crtc_controller_registers
+            .read(emulation_mode, CrtcControllerIndex::Overflow)
+            & !0x10;
+        let maximum_scan_line = self
+            .crtc_controller_registers
+            .read(emulation_mode, CrtcControllerIndex::MaximumScanLine)
+            & !0x40;
+        self.crtc_controller_registers.write(
+            emulation_mode,
+            CrtcControllerIndex::LineCompare,
+            line_compare as u8,
+        );
+        self.crtc_controller_registers.write(
+            emulation_mode,
+            CrtcControllerIndex::Overflow,
+            overflow | ((line_compare >> 4) & 0x10) as u8,
+        );
+        self.crtc_controller_registers.write(
+            emulation_mode,
+            CrtcControllerIndex::MaximumScanLine,
+            maximum_scan_line | ((line_compare >> 3) & 0x40) as u8,
+        );
+    }
+
+    /// Shifts the display left by `pixels` through the attribute controller's
+    /// horizontal pixel panning register `(0x13)`. Together with the byte
+    /// granular start address, this allows scrolling one pixel at a time.
+    ///
+    /// The register value is translated from `pixels` for the current mode:
+    ///
+    /// - Text modes with 9 dot wide characters pan by `0..=8` pixels, where the
+    ///   register holds `8` for no panning and `0..=7` for 1 to 8 pixels.
+    /// - 256 color modes pan by `0..=3` pixels, with the register counting half
+    ///   pixels.
+    /// - All other modes pan by `0..=7` pixels.
+    ///
+    /// The screen is unblanked afterwards since selecting the register clears
+    /// the palette address source bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` is outside of the range allowed by the current mode.
+    pub fn set_pixel_pan(&mut self, pixels: u8) {
+        let emulation_mode = self.get_emulation_mode();
+        let nine_dot = self.sequencer_registers.read(SequencerIndex::ClockingMode) & 0x01 == 0;
+        let eight_bit_color = self
+            .attribute_controller_registers
+            .read(emulation_mode, AttributeControllerIndex::ModeControl)
+            & 0x40
+            != 0;
+        let value = if nine_dot {
+            assert!(pixels <= 8, "9 dot text modes pan by 0..=8 pixels");
+            (pixels + 8) % 9
+        } else if eight_bit_color {
+            assert!(pixels <= 3, "256 color modes pan by 0..=3 pixels");
+            pixels * 2
+        } else {
+            assert!(pixels <= 7, "pixel panning is limited to 0..=7 pixels");
+            pixels
+        };
+
+        self.attribute_controller_registers.write(
+            emulation_mode,
+            AttributeControllerIndex::HorizontalPixelPanning,
+            value,
+        );
+        self.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+    }
+
+    /// Blocks until the start of the next vertical retrace.
+    ///
+    /// This busy-waits on the vertical retrace bit `(0x08)` of the input status #1
+    /// register, first waiting for any retrace in progress to finish and then for
+    /// a new one to begin. Drawing right after this returns helps avoid tearing.
+    pub fn wait_for_vsync(&mut self) {
+        let emulation_mode = self.get_emulation_mode();
+        while self.general_registers.read_st01(emulation_mode) & 0x08 != 0 {}
+        while self.general_registers.read_st01(emulation_mode) & 0x08 == 0 {}
+    }
+
+    /// Fades the current palette to black over `steps` frames.
+    ///
+    /// This waits for a vertical retrace before loading each step of the fade,
+    /// so it blocks for roughly `steps` frames.
+    pub fn fade_to_black(&mut self, steps: u8) {
+        self.fade_to(&[0; PALETTE_SIZE], steps);
+    }
+
+    /// Fades from the current palette back in to `target` over `steps` frames,
+    /// such as after `Vga::fade_to_black`. This is the same as `Vga::fade_to`.
+    ///
+    /// This waits for a vertical retrace before loading each step of the fade,
+    /// so it blocks for roughly `steps` frames.
+    pub fn fade_from(&mut self, target: &[u8; PALETTE_SIZE], steps: u8) {
+        self.fade_to(target, steps);
+    }
+
+    /// Fades the current palette to `target` over `steps` frames.
+    ///
+    /// This waits for a vertical retrace before loading each step of the fade,
+    /// so it blocks for roughly `steps` frames.
+    pub fn fade_to(&mut self, target: &[u8; PALETTE_SIZE], steps: u8) {
+        let mut start = [0; PALETTE_SIZE];
+        let mut palette = [0; PALETTE_SIZE];
+        self.color_palette_registers.read_palette(&mut start);
+
+        let steps = i32::from(steps.max(1));
+        for step in 1..=steps {
+            for (i, value) in palette.iter_mut().enumerate() {
+                let from = i32::from(start[i]);
+                let to = i32::from(target[i]);
+                *value = (from + (to - from) * step / steps) as u8;
+            }
+            self.wait_for_vsync();
+            self.color_palette_registers.load_palette(&palette);
+        }
+    }
+
+    /// Reads the current value of every register programmed by `Vga::set_video_mode`,
+    /// along with the palette, so the current mode can be restored later with
+    /// `Vga::restore_state`.
+    ///
+    /// Video memory isn't saved, so the text on the screen and any custom font
+    /// are lost once another mode draws over them.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use vga::vga::{VideoMode, VGA};
+    ///
+    /// let mut vga = VGA.lock();
+    /// let state = vga.save_state();
+    /// vga.set_video_mode(VideoMode::Mode640x480x16);
+    /// // ...
+    /// // Back in text mode with the standard font and a blank screen.
+    /// vga.restore_state(&state);
+    /// ```
+    pub fn save_state(&mut self) -> VgaState {
+        let emulation_mode = self.get_emulation_mode();
+        let mut state = VgaState {
+            miscellaneous_output: self.general_registers.read_msr(),
+            sequencer_registers: [(SequencerIndex::SequencerReset, 0); 5],
+            crtc_controller_registers: [(CrtcControllerIndex::HorizontalTotal, 0); 25],
+            graphics_controller_registers: [(GraphicsControllerIndex::SetReset, 0); 9],
+            attribute_controller_registers: [(AttributeControllerIndex::PaletteRegister0, 0); 21],
+            palette: [0; PALETTE_SIZE],
+            video_mode: self.most_recent_video_mode,
+        };
+
+        for (register, (index, _)) in state
+            .sequencer_registers
+            .iter_mut()
+            .zip(STATE_REGISTERS.sequencer_registers)
+        {
+            *register = (*index, self.sequencer_registers.read(*index));
+        }
+
+        for (register, (index, _)) in state
+            .crtc_controller_registers
+            .iter_mut()
+            .zip(STATE_REGISTERS.crtc_controller_registers)
+        {
+            let value = self.crtc_controller_registers.read(emulation_mode, *index);
+            *register = (*index, value);
+        }
+
+        for (register, (index, _)) in state
+            .graphics_controller_registers
+            .iter_mut()
+            .zip(STATE_REGISTERS.graphics_controller_registers)
+        {
+            *register = (*index, self.graphics_controller_registers.read(*index));
+        }
+
+        // The palette registers can only be read while the screen is blanked.
+        self.attribute_controller_registers
+            .blank_screen(emulation_mode);
+        for (register, (index, _)) in state
+            .attribute_controller_registers
+            .iter_mut()
+            .zip(STATE_REGISTERS.attribute_controller_registers)
+        {
+            let value = self
+                .attribute_controller_registers
+                .read(emulation_mode, *index);
+            *register = (*index, value);
+        }
+        self.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+
+        self.color_palette_registers
+            .read_palette(&mut state.palette);
+        state
+    }
+
+    /// Writes back the registers and palette of a `VgaState`
+    /// previously returned by `Vga::save_state`.
+    ///
+    /// Graphics modes draw over the characters in planes 0 and 1 and the font
+    /// in plane 2, so when `state` is in a text mode the standard font for its
+    /// character height is reloaded and every cell is cleared to a light grey
+    /// on black space. The text that was on the screen isn't brought back.
+    pub fn restore_state(&mut self, state: &VgaState) {
+        self.write_registers(
+            state.miscellaneous_output,
+            &state.sequencer_registers,
+            &state.crtc_controller_registers,
+            &state.graphics_controller_registers,
+            &state.attribute_controller_registers,
+        );
+        match state.video_mode {
+            Some(VideoMode::Mode40x25) | Some(VideoMode::Mode80x25) => {
+                self.load_font(&TEXT_8X16_FONT);
+                self.clear_text_buffer();
+            }
+            Some(VideoMode::Mode40x50) | Some(VideoMode::Mode80x50) => {
+                self.load_font(&TEXT_8X8_FONT);
+                self.clear_text_buffer();
+            }
+            _ => (),
+        }
+        self.color_palette_registers.load_palette(&state.palette);
+        self.most_recent_video_mode = state.video_mode;
+    }
+
     /// Loads a vga text mode font as specified by `vga_font`.
     pub fn load_font(&mut self, vga_font: &VgaFont) {
+        let size = usize::from(vga_font.characters) * usize::from(vga_font.character_height);
+        self.load_text_font(&vga_font.font_data[..size], vga_font.character_height as u8);
+    }
+
+    /// Loads a text mode font into plane 2, where `font` holds `height` bytes
+    /// per character, one per row with the most significant bit as the leftmost
+    /// pixel. Each character is given a 32 byte slot, so up to 256 characters
+    /// with a `height` of up to 32 can be loaded.
+    ///
+    /// This doesn't change the character height of the current mode, so `height`
+    /// should match it. `fonts::TEXT_8X16_FONT` holds the standard vga font.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `height` is 0 or greater than 32.
+    pub fn load_text_font(&mut self, font: &[u8], height: u8) {
+        assert!(
+            (1..=32).contains(&height),
+            "height must be in the range 1..=32"
+        );
+        let height = usize::from(height);
+        let characters = (font.len() / height).min(256);
+
         // Save registers
         let (
             plane_mask,
@@ -156,16 +667,14 @@ impl Vga {
         // Write font to plane
         self.sequencer_registers.set_plane_mask(PlaneMask::PLANE2);
 
-        let frame_buffer = u32::from(self.get_frame_buffer()) as *mut u8;
+        let frame_buffer = self.get_frame_buffer().as_ptr();
 
-        for character in 0..vga_font.characters {
-            for row in 0..vga_font.character_height {
+        for character in 0..characters {
+            for row in 0..height {
                 let offset = (character * 32) + row;
-                let font_offset = (character * vga_font.character_height) + row;
+                let font_offset = (character * height) + row;
                 unsafe {
-                    frame_buffer
-                        .offset(offset as isize)
-                        .write_volatile(vga_font.font_data[font_offset as usize]);
+                    frame_buffer.add(offset).write_volatile(font[font_offset]);
                 }
             }
         }
@@ -179,6 +688,18 @@ impl Vga {
         );
     }
 
+    /// Fills the whole text mode frame buffer with light grey on black spaces.
+    fn clear_text_buffer(&mut self) {
+        let frame_buffer = self.get_frame_buffer();
+        let blank = ScreenCharacter::new(b' ', TextModeColor::LIGHT_GREY_ON_BLACK);
+        let characters = frame_buffer.as_ptr() as *mut ScreenCharacter;
+        for i in 0..frame_buffer.len() / 2 {
+            unsafe {
+                characters.add(i).write_volatile(blank);
+            }
+        }
+    }
+
     fn restore_font_registers(
         &mut self,
         plane_mask: u8,
@@ -215,14 +736,31 @@ impl Vga {
     }
 
     fn set_registers(&mut self, configuration: &VgaConfiguration) {
-        let emulation_mode = self.get_emulation_mode();
+        self.write_registers(
+            configuration.miscellaneous_output,
+            configuration.sequencer_registers,
+            configuration.crtc_controller_registers,
+            configuration.graphics_controller_registers,
+            configuration.attribute_controller_registers,
+        );
+    }
 
+    fn write_registers(
+        &mut self,
+        miscellaneous_output: u8,
+        sequencer_registers: &[(SequencerIndex, u8)],
+        crtc_controller_registers: &[(CrtcControllerIndex, u8)],
+        graphics_controller_registers: &[(GraphicsControllerIndex, u8)],
+        attribute_controller_registers: &[(AttributeControllerIndex, u8)],
+    ) {
         // Set miscellaneous output
-        self.general_registers
-            .write_msr(configuration.miscellaneous_output);
+        self.general_registers.write_msr(miscellaneous_output);
+
+        // The emulation mode is selected by the miscellaneous output register.
+        let emulation_mode = self.get_emulation_mode();
 
         // Set the sequencer registers.
-        for (index, value) in configuration.sequencer_registers {
+        for (index, value) in sequencer_registers {
             self.sequencer_registers.write(*index, *value);
         }
 
@@ -230,13 +768,13 @@ impl Vga {
         self.unlock_crtc_registers(emulation_mode);
 
         // Set the crtc registers.
-        for (index, value) in configuration.crtc_controller_registers {
+        for (index, value) in crtc_controller_registers {
             self.crtc_controller_registers
                 .write(emulation_mode, *index, *value);
         }
 
         // Set the grx registers.
-        for (index, value) in configuration.graphics_controller_registers {
+        for (index, value) in graphics_controller_registers {
             self.graphics_controller_registers.write(*index, *value);
         }
 
@@ -245,7 +783,7 @@ impl Vga {
             .blank_screen(emulation_mode);
 
         // Set the arx registers.
-        for (index, value) in configuration.attribute_controller_registers {
+        for (index, value) in attribute_controller_registers {
             self.attribute_controller_registers
                 .write(emulation_mode, *index, *value);
         }
@@ -258,21 +796,43 @@ impl Vga {
     /// Sets the video card to Mode 40x25.
     fn set_video_mode_40x25(&mut self) {
         self.set_registers(&MODE_40X25_CONFIGURATION);
+        self.load_font(&TEXT_8X16_FONT);
         self.most_recent_video_mode = Some(VideoMode::Mode40x25);
     }
 
     /// Sets the video card to Mode 40x50.
     fn set_video_mode_40x50(&mut self) {
         self.set_registers(&MODE_40X50_CONFIGURATION);
+        self.load_font(&TEXT_8X8_FONT);
         self.most_recent_video_mode = Some(VideoMode::Mode40x50);
     }
 
     /// Sets the video card to Mode 80x25.
     fn set_video_mode_80x25(&mut self) {
         self.set_registers(&MODE_80X25_CONFIGURATION);
+        self.load_font(&TEXT_8X16_FONT);
         self.most_recent_video_mode = Some(VideoMode::Mode80x25);
     }
 
+    /// Sets the video card to Mode 80x50.
+    fn set_video_mode_80x50(&mut self) {
+        self.set_registers(&MODE_80X50_CONFIGURATION);
+        self.load_font(&TEXT_8X8_FONT);
+        self.most_recent_video_mode = Some(VideoMode::Mode80x50);
+    }
+
+    /// Sets the video card to Mode 320x200x4.
+    fn set_video_mode_320x200x4(&mut self) {
+        self.set_registers(&MODE_320X200X4_CONFIGURATION);
+        self.most_recent_video_mode = Some(VideoMode::Mode320x200x4);
+    }
+
+    /// Sets the video card to Mode 320x200x16.
+    fn set_video_mode_320x200x16(&mut self) {
+        self.set_registers(&MODE_320X200X16_CONFIGURATION);
+        self.most_recent_video_mode = Some(VideoMode::Mode320x200x16);
+    }
+
     /// Sets the video card to Mode 320x200x256.
     fn set_video_mode_320x200x256(&mut self) {
         self.set_registers(&MODE_320X200X256_CONFIGURATION);
@@ -285,6 +845,12 @@ impl Vga {
         self.most_recent_video_mode = Some(VideoMode::Mode320x240x256);
     }
 
+    /// Sets the video card to Mode 640x350x16.
+    fn set_video_mode_640x350x16(&mut self) {
+        self.set_registers(&MODE_640X350X16_CONFIGURATION);
+        self.most_recent_video_mode = Some(VideoMode::Mode640x350x16);
+    }
+
     /// Sets the video card to Mode 640x480x16.
     fn set_video_mode_640x480x16(&mut self) {
         self.set_registers(&MODE_640X480X16_CONFIGURATION);
@@ -324,3 +890,20 @@ impl Vga {
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_frame_buffer() {
+        assert_eq!(FrameBuffer::GraphicsMode.as_ptr() as usize, 0xA0000);
+        assert_eq!(FrameBuffer::GraphicsMode.len(), 0x10000);
+        assert_eq!(FrameBuffer::GraphicsMode.segment(), 0xA000);
+        assert_eq!(FrameBuffer::CgaMode.as_ptr() as usize, 0xB8000);
+        assert_eq!(FrameBuffer::CgaMode.len(), 0x8000);
+        assert_eq!(FrameBuffer::CgaMode.segment(), 0xB800);
+        assert_eq!(FrameBuffer::MdaMode.segment(), 0xB000);
+        assert_eq!(FrameBuffer::from(0x1), FrameBuffer::GraphicsMode);
+    }
+}
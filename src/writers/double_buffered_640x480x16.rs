@@ -0,0 +1,411 @@
+use super::{Graphics640x480x16, GraphicsWriter, Screen};
+use crate::{
+    colors::Color16,
+    drawing::{clip_line, Bresenham, Point, Rect},
+    fonts::basic_glyph,
+    registers::{PlaneMask, RasterOp, WriteMode},
+    vga::VGA,
+};
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 480;
+const SIZE: usize = (WIDTH * HEIGHT) / 8;
+const BUFFER_SIZE: usize = (WIDTH * HEIGHT) / 2;
+const WIDTH_IN_BYTES: usize = WIDTH / 8;
+const SCREEN: Rect = Rect::new(0, 0, WIDTH, HEIGHT);
+
+/// The region of the back buffer that changed since the last `present`,
+/// with exclusive `x_end` and `y_end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DirtyRegion {
+    x_start: usize,
+    y_start: usize,
+    x_end: usize,
+    y_end: usize,
+}
+
+impl DirtyRegion {
+    const FULL: DirtyRegion = DirtyRegion {
+        x_start: 0,
+        y_start: 0,
+        x_end: WIDTH,
+        y_end: HEIGHT,
+    };
+
+    fn union(self, other: DirtyRegion) -> DirtyRegion {
+        DirtyRegion {
+            x_start: self.x_start.min(other.x_start),
+            y_start: self.y_start.min(other.y_start),
+            x_end: self.x_end.max(other.x_end),
+            y_end: self.y_end.max(other.y_end),
+        }
+    }
+}
+
+/// An off-screen back buffer for vga graphics mode 640x480x16.
+///
+/// All drawing happens in a caller provided buffer, packed as 4 bits per pixel
+/// with the even pixel of each pair in the high nibble. Nothing is visible until
+/// `present` copies the back buffer to the frame buffer, which avoids the flicker
+/// of partially drawn frames.
+///
+/// The bounding rectangle of everything drawn since the last `present` is
+/// tracked, so `present` only needs to copy the region that actually changed.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::colors::Color16;
+/// use vga::writers::{DoubleBuffered640x480x16, Graphics640x480x16, GraphicsWriter};
+///
+/// static mut BACK_BUFFER: [u8; DoubleBuffered640x480x16::BUFFER_SIZE] =
+///     [0; DoubleBuffered640x480x16::BUFFER_SIZE];
+///
+/// let mode = Graphics640x480x16::new();
+/// mode.set_mode();
+///
+/// let mut buffer = DoubleBuffered640x480x16::new(unsafe { &mut *&raw mut BACK_BUFFER });
+/// buffer.clear_screen(Color16::Black);
+/// buffer.draw_line((80, 60), (540, 420), Color16::White);
+/// buffer.present();
+/// ```
+#[derive(Debug)]
+pub struct DoubleBuffered640x480x16<'a> {
+    buffer: &'a mut [u8; BUFFER_SIZE],
+    dirty: Option<DirtyRegion>,
+}
+
+impl Screen for DoubleBuffered640x480x16<'_> {
+    const WIDTH: usize = WIDTH;
+    const HEIGHT: usize = HEIGHT;
+    const SIZE: usize = SIZE;
+}
+
+impl<'a> DoubleBuffered640x480x16<'a> {
+    /// The size in bytes of the back buffer required by `DoubleBuffered640x480x16::new`.
+    pub const BUFFER_SIZE: usize = BUFFER_SIZE;
+
+    /// Creates a new `DoubleBuffered640x480x16` that draws into `buffer`.
+    ///
+    /// The whole buffer starts out dirty, so the first `present` copies
+    /// all of it to the frame buffer.
+    pub fn new(buffer: &'a mut [u8; BUFFER_SIZE]) -> DoubleBuffered640x480x16<'a> {
+        DoubleBuffered640x480x16 {
+            buffer,
+            dirty: Some(DirtyRegion::FULL),
+        }
+    }
+
+    /// Marks the rectangle starting at `top_left` with the given `width` and
+    /// `height` as changed, so it's copied by the next `present`. This is only
+    /// needed when the back buffer is modified outside of the drawing methods.
+    pub fn mark_dirty(&mut self, top_left: impl Into<Point>, width: usize, height: usize) {
+        self.mark_dirty_rect(Rect::from_point(top_left, width, height));
+    }
+
+    /// Marks `rect` as changed, like `mark_dirty`.
+    pub fn mark_dirty_rect(&mut self, rect: Rect) {
+        let rect = rect.intersection(&SCREEN);
+        if rect.is_empty() {
+            return;
+        }
+
+        let (columns, rows) = (rect.columns(), rect.rows());
+        let region = DirtyRegion {
+            x_start: columns.start,
+            y_start: rows.start,
+            x_end: columns.end,
+            y_end: rows.end,
+        };
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(region),
+            None => region,
+        });
+    }
+
+    /// Releases the back buffer.
+    pub fn into_inner(self) -> &'a mut [u8; BUFFER_SIZE] {
+        self.buffer
+    }
+
+    /// Clears the back buffer by setting all pixels to the specified `color`.
+    pub fn clear_screen(&mut self, color: Color16) {
+        let color = u8::from(color);
+        self.buffer.fill(color << 4 | color);
+        self.dirty = Some(DirtyRegion::FULL);
+    }
+
+    /// Sets the given pixel at `(x, y)` to the given `color`. Pixels
+    /// outside of the screen are ignored.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color16) {
+        if x >= WIDTH || y >= HEIGHT {
+            return;
+        }
+
+        self.write_pixel(x, y, color);
+        self.mark_dirty_rect(Rect::new(x as isize, y as isize, 1, 1));
+    }
+
+    /// Returns the `Color16` of the pixel at `(x, y)` in the back buffer,
+    /// or `None` if `(x, y)` is outside of the screen.
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<Color16> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+
+        let byte = self.buffer[(y * WIDTH + x) / 2];
+        let nibble = match x & 1 {
+            0 => byte >> 4,
+            _ => byte & 0x0F,
+        };
+        Color16::from_nibble(nibble)
+    }
+
+    /// Draws a line from `start` to `end` with the specified `color`.
+    /// The line is clipped to the screen, so `start` and `end` may lie
+    /// anywhere on the plane.
+    pub fn draw_line(&mut self, start: impl Into<Point>, end: impl Into<Point>, color: Color16) {
+        if let Some((start, end)) = clip_line(start, end, &SCREEN) {
+            let (x, y) = (start.0.min(end.0), start.1.min(end.1));
+            let width = start.0.abs_diff(end.0) + 1;
+            let height = start.1.abs_diff(end.1) + 1;
+            self.mark_dirty_rect(Rect::new(x, y, width, height));
+            for (x, y) in Bresenham::new(start, end) {
+                self.write_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+
+    /// Fills the rectangle starting at `top_left` with the given `width`,
+    /// `height` and `color`. Any part of the rectangle that falls outside
+    /// of the screen is clipped.
+    ///
+    /// Pixels that share a byte with a pixel outside of the rectangle are
+    /// written one at a time, while the rest of each row is filled a whole
+    /// byte at a time.
+    pub fn fill_rect(
+        &mut self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        color: Color16,
+    ) {
+        let rect = Rect::from_point(top_left, width, height).intersection(&SCREEN);
+        if rect.is_empty() {
+            return;
+        }
+
+        self.mark_dirty_rect(rect);
+        let byte = u8::from(color) << 4 | u8::from(color);
+        for row in rect.rows() {
+            let mut columns = rect.columns();
+            if columns.start & 1 == 1 {
+                self.write_pixel(columns.start, row, color);
+                columns.start += 1;
+            }
+            if columns.end & 1 == 1 && columns.end > columns.start {
+                columns.end -= 1;
+                self.write_pixel(columns.end, row, color);
+            }
+            if columns.start < columns.end {
+                let start = (row * WIDTH + columns.start) / 2;
+                let end = (row * WIDTH + columns.end) / 2;
+                self.buffer[start..end].fill(byte);
+            }
+        }
+    }
+
+    /// Draws a character at the given `(x, y)` coordinant to the specified `color`.
+    /// Any part of the character that falls outside of the screen is clipped.
+    pub fn draw_character(&mut self, x: usize, y: usize, character: char, color: Color16) {
+        if x >= WIDTH || y >= HEIGHT {
+            return;
+        }
+
+        let character = basic_glyph(character);
+        self.mark_dirty_rect(Rect::new(x as isize, y as isize, 8, 8));
+        for (row, byte) in character.iter().enumerate() {
+            for bit in 0..8 {
+                if *byte & 1 << bit != 0 && x + bit < WIDTH && y + row < HEIGHT {
+                    self.write_pixel(x + bit, y + row, color);
+                }
+            }
+        }
+    }
+
+    /// Sets the pixel at `(x, y)`, which must be on the screen, without
+    /// marking it as dirty.
+    #[inline]
+    fn write_pixel(&mut self, x: usize, y: usize, color: Color16) {
+        let offset = (y * WIDTH + x) / 2;
+        let byte = &mut self.buffer[offset];
+        *byte = match x & 1 {
+            0 => (*byte & 0x0F) | u8::from(color) << 4,
+            _ => (*byte & 0xF0) | u8::from(color),
+        };
+    }
+
+    /// Copies the region of the back buffer that changed since the last
+    /// `present` to the vga frame buffer.
+    pub fn present(&mut self) {
+        if let Some(dirty) = self.dirty.take() {
+            self.copy_region(dirty);
+        }
+    }
+
+    /// Copies the whole back buffer to the vga frame buffer, regardless
+    /// of which regions changed.
+    pub fn present_full(&mut self) {
+        self.dirty = None;
+        self.copy_region(DirtyRegion::FULL);
+    }
+
+    /// Copies `region` of the back buffer to the vga frame buffer, one plane
+    /// at a time. The region is widened to whole frame buffer bytes.
+    fn copy_region(&self, region: DirtyRegion) {
+        let frame_buffer = Graphics640x480x16::new().get_frame_buffer();
+        let first_byte = region.x_start / 8;
+        let last_byte = region.x_end.div_ceil(8);
+        let mut vga = VGA.lock();
+        let raster_op = vga.graphics_controller_registers.get_raster_op();
+        vga.graphics_controller_registers
+            .set_raster_op(RasterOp::Replace);
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode0);
+        vga.graphics_controller_registers
+            .write_enable_set_reset(0x0);
+        vga.graphics_controller_registers.set_bit_mask(0xFF);
+
+        for plane in PlaneMask::ALL_PLANES.iter() {
+            vga.sequencer_registers
+                .set_plane_mask(PlaneMask::from_planes(&[plane]));
+            for row in region.y_start..region.y_end {
+                for column in first_byte..last_byte {
+                    let offset = column + row * WIDTH_IN_BYTES;
+                    let byte = self.plane_byte(offset, plane);
+                    unsafe {
+                        frame_buffer.add(offset).write_volatile(byte);
+                    }
+                }
+            }
+        }
+
+        vga.sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+        vga.graphics_controller_registers.set_raster_op(raster_op);
+    }
+
+    /// Gathers the bits of `plane` for the 8 pixels stored at
+    /// `offset` in the frame buffer.
+    #[inline]
+    fn plane_byte(&self, offset: usize, plane: u8) -> u8 {
+        let start = offset * 4;
+        let mut byte = 0;
+        for (i, pixels) in self.buffer[start..start + 4].iter().enumerate() {
+            let high = (pixels >> (4 + plane)) & 0x1;
+            let low = (pixels >> plane) & 0x1;
+            byte |= high << (7 - i * 2) | low << (6 - i * 2);
+        }
+        byte
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_and_get_pixel() {
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut mode = DoubleBuffered640x480x16::new(&mut buffer);
+        mode.clear_screen(Color16::Blue);
+        mode.set_pixel(10, 20, Color16::Yellow);
+        mode.set_pixel(11, 20, Color16::Red);
+        mode.set_pixel(WIDTH, 0, Color16::White);
+        assert_eq!(mode.get_pixel(9, 20), Some(Color16::Blue));
+        assert_eq!(mode.get_pixel(10, 20), Some(Color16::Yellow));
+        assert_eq!(mode.get_pixel(11, 20), Some(Color16::Red));
+        assert_eq!(mode.get_pixel(WIDTH, 0), None);
+    }
+
+    #[test]
+    fn test_fill_rect() {
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut mode = DoubleBuffered640x480x16::new(&mut buffer);
+        mode.clear_screen(Color16::Blue);
+        mode.dirty = None;
+        for &(x, width) in [(3, 1), (4, 1), (5, 6), (6, 6), (9, 7)].iter() {
+            mode.fill_rect((x, 0), width, 1, Color16::Red);
+            mode.fill_rect((x, 1), width, 1, Color16::Red);
+            for column in 0..20 {
+                let expected = if (x..x + width).contains(&column) {
+                    Color16::Red
+                } else {
+                    Color16::Blue
+                };
+                assert_eq!(mode.get_pixel(column, 0), Some(expected));
+                assert_eq!(mode.get_pixel(column, 1), Some(expected));
+            }
+            mode.fill_rect((0, 0), 20, 2, Color16::Blue);
+        }
+
+        mode.fill_rect((WIDTH - 3, HEIGHT - 1), 10, 10, Color16::Red);
+        assert_eq!(mode.get_pixel(WIDTH - 4, HEIGHT - 1), Some(Color16::Blue));
+        assert_eq!(mode.get_pixel(WIDTH - 3, HEIGHT - 1), Some(Color16::Red));
+        assert_eq!(mode.get_pixel(WIDTH - 1, HEIGHT - 1), Some(Color16::Red));
+    }
+
+    #[test]
+    fn test_plane_byte() {
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut mode = DoubleBuffered640x480x16::new(&mut buffer);
+        // `Color16::Green` only has plane 1 set.
+        mode.set_pixel(8, 0, Color16::Green);
+        mode.set_pixel(15, 0, Color16::White);
+        assert_eq!(mode.plane_byte(1, 0), 0b0000_0001);
+        assert_eq!(mode.plane_byte(1, 1), 0b1000_0001);
+        assert_eq!(mode.plane_byte(0, 1), 0);
+    }
+
+    #[test]
+    fn test_dirty_region() {
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut mode = DoubleBuffered640x480x16::new(&mut buffer);
+        assert_eq!(mode.dirty, Some(DirtyRegion::FULL));
+
+        mode.dirty = None;
+        mode.set_pixel(10, 20, Color16::Yellow);
+        mode.fill_rect((100, 5), 10, 10, Color16::Red);
+        mode.mark_dirty_rect(Rect::new(600, 470, 100, 100));
+        assert_eq!(
+            mode.dirty,
+            Some(DirtyRegion {
+                x_start: 10,
+                y_start: 5,
+                x_end: WIDTH,
+                y_end: HEIGHT,
+            })
+        );
+
+        mode.dirty = None;
+        mode.mark_dirty((10, 10), 0, 10);
+        mode.set_pixel(WIDTH, 0, Color16::White);
+        mode.draw_character(WIDTH, 0, 'A', Color16::White);
+        assert_eq!(mode.dirty, None);
+
+        // Only the visible part of the line, starting at `(0, 6)`, is dirty.
+        mode.draw_line((-10, 3), (5, 8), Color16::White);
+        mode.draw_character(WIDTH - 4, 30, 'A', Color16::White);
+        assert_eq!(
+            mode.dirty,
+            Some(DirtyRegion {
+                x_start: 0,
+                y_start: 6,
+                x_end: WIDTH,
+                y_end: 38,
+            })
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use super::{GraphicsWriter, Screen};
 use crate::{
     colors::DEFAULT_PALETTE,
-    drawing::{Bresenham, Point},
+    drawing::{clip_line, Bresenham, GradientDirection, Point, Rect},
+    fonts::basic_glyph,
     vga::{VideoMode, VGA},
 };
-use font8x8::UnicodeFonts;
 
 const WIDTH: usize = 320;
 const HEIGHT: usize = 200;
@@ -47,9 +47,14 @@ impl GraphicsWriter<u8> for Graphics320x200x256 {
             self.get_frame_buffer().write_bytes(color, Self::SIZE);
         }
     }
-    fn draw_line(&self, start: Point<isize>, end: Point<isize>, color: u8) {
-        for (x, y) in Bresenham::new(start, end) {
-            self.set_pixel(x as usize, y as usize, color);
+    /// The line is clipped to the screen, so `start` and `end` may lie
+    /// anywhere on the plane.
+    fn draw_line(&self, start: impl Into<Point>, end: impl Into<Point>, color: u8) {
+        let screen = Rect::new(0, 0, WIDTH, HEIGHT);
+        if let Some((start, end)) = clip_line(start, end, &screen) {
+            for (x, y) in Bresenham::new(start, end) {
+                self.set_pixel(x as usize, y as usize, color);
+            }
         }
     }
     fn set_pixel(&self, x: usize, y: usize, color: u8) {
@@ -59,11 +64,7 @@ impl GraphicsWriter<u8> for Graphics320x200x256 {
         }
     }
     fn draw_character(&self, x: usize, y: usize, character: char, color: u8) {
-        let character = match font8x8::BASIC_FONTS.get(character) {
-            Some(character) => character,
-            // Default to a filled block if the character isn't found
-            None => font8x8::unicode::BLOCK_UNICODE[8].byte_array(),
-        };
+        let character = basic_glyph(character);
 
         for (row, byte) in character.iter().enumerate() {
             for bit in 0..8 {
@@ -89,4 +90,99 @@ impl Graphics320x200x256 {
     pub const fn new() -> Graphics320x200x256 {
         Graphics320x200x256
     }
+
+    /// Returns the color of the pixel at `(x, y)`, or `None` if
+    /// `(x, y)` is outside of the screen.
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<u8> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+
+        let offset = (y * WIDTH) + x;
+        unsafe { Some(self.get_frame_buffer().add(offset).read_volatile()) }
+    }
+
+    /// Draws the `width` by `height` image in `pixels`, one palette index per
+    /// pixel stored row by row, with its top left corner at `origin`. Any part
+    /// of the image that falls outside of the screen is clipped.
+    ///
+    /// Since the frame buffer is linear, each row of the image that's visible
+    /// on screen is copied as a single run of bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` has fewer than `width * height` bytes.
+    pub fn draw_image(&self, origin: impl Into<Point>, width: usize, height: usize, pixels: &[u8]) {
+        assert!(pixels.len() >= width * height, "not enough image data");
+
+        let image = Rect::from_point(origin, width, height);
+        let region = image.intersection(&Rect::new(0, 0, WIDTH, HEIGHT));
+        if region.is_empty() {
+            return;
+        }
+        let skipped = (region.x - image.x) as usize;
+        let frame_buffer = self.get_frame_buffer();
+        for y in region.rows() {
+            let row = (y as isize - image.y) as usize;
+            let row = &pixels[row * width + skipped..][..region.width];
+            unsafe {
+                frame_buffer
+                    .add(y * WIDTH + region.x as usize)
+                    .copy_from_nonoverlapping(row.as_ptr(), row.len());
+            }
+        }
+    }
+
+    /// Fills `rect` with a gradient from the palette index `start` to `end` along
+    /// `direction`, stepping through the indices in between. Any part of `rect`
+    /// that falls outside of the screen is clipped.
+    ///
+    /// This reads as a smooth ramp when the palette entries from `start` to `end`
+    /// hold the intermediate colors, for example as loaded with
+    /// `ColorPaletteRegisters::set_color`.
+    pub fn fill_rect_gradient(&self, rect: Rect, start: u8, end: u8, direction: GradientDirection) {
+        let screen = Rect::new(0, 0, WIDTH, HEIGHT);
+        let region = rect.intersection(&screen);
+        let (position, length) = match direction {
+            GradientDirection::Horizontal => ((region.x - rect.x) as usize, rect.width),
+            GradientDirection::Vertical => ((region.y - rect.y) as usize, rect.height),
+        };
+        let last = length.saturating_sub(1).max(1) as isize;
+        let range = isize::from(end) - isize::from(start);
+        let color_at = |position: usize| {
+            let step = (range * position as isize * 2 + range.signum() * last) / (last * 2);
+            (isize::from(start) + step) as u8
+        };
+
+        let frame_buffer = self.get_frame_buffer();
+        let (columns, rows) = (region.columns(), region.rows());
+        for y in rows.clone() {
+            for x in columns.clone() {
+                let position = match direction {
+                    GradientDirection::Horizontal => position + x - columns.start,
+                    GradientDirection::Vertical => position + y - rows.start,
+                };
+                unsafe {
+                    frame_buffer
+                        .add(y * WIDTH + x)
+                        .write_volatile(color_at(position));
+                }
+            }
+        }
+    }
+
+    /// Fills the screen with a 16 by 16 grid showing every color of the
+    /// palette, ordered by index from left to right and top to bottom. This is
+    /// useful to confirm the mode and palette were set up correctly.
+    pub fn draw_test_pattern(&self) {
+        let frame_buffer = self.get_frame_buffer();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let color = (y * 16 / HEIGHT) * 16 + x * 16 / WIDTH;
+                unsafe {
+                    frame_buffer.add(y * WIDTH + x).write_volatile(color as u8);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use super::{GraphicsWriter, Screen};
+use crate::{
+    colors::DEFAULT_PALETTE,
+    drawing::{clip_line, Bresenham, Point, Rect},
+    fonts::basic_glyph,
+    vga::{FrameBuffer, VideoMode, VGA},
+};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 200;
+const SIZE: usize = (WIDTH * HEIGHT) / 4;
+const WIDTH_IN_BYTES: usize = WIDTH / 4;
+const BANK_SIZE: usize = SIZE / 2;
+const ODD_BANK_OFFSET: usize = 0x2000;
+
+/// A basic interface for interacting with vga graphics mode 320x200x4.
+///
+/// This is the cga compatible 4 color mode. Each byte of the frame buffer holds
+/// 4 pixels, 2 bits each with the leftmost pixel in the highest bits. Even rows
+/// are stored at the start of the frame buffer and odd rows in a second bank
+/// starting at offset `0x2000`.
+///
+/// Colors are in the range `0..4` and any higher bits are ignored. With the
+/// default palette they are black, light cyan, light magenta and white.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::writers::{Graphics320x200x4, GraphicsWriter};
+///
+/// let mode = Graphics320x200x4::new();
+/// mode.set_mode();
+/// mode.clear_screen(0);
+/// mode.draw_line((60, 20), (260, 20), 3);
+/// mode.draw_line((60, 20), (60, 180), 3);
+/// mode.draw_line((60, 180), (260, 180), 3);
+/// mode.draw_line((260, 180), (260, 20), 3);
+/// mode.draw_line((60, 40), (260, 40), 3);
+/// for (offset, character) in "Hello World!".chars().enumerate() {
+///     mode.draw_character(118 + offset * 8, 27, character, 1);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Graphics320x200x4;
+
+impl Screen for Graphics320x200x4 {
+    const WIDTH: usize = WIDTH;
+    const HEIGHT: usize = HEIGHT;
+    const SIZE: usize = SIZE;
+}
+
+impl GraphicsWriter<u8> for Graphics320x200x4 {
+    fn clear_screen(&self, color: u8) {
+        // Repeat the color for all 4 pixels of each byte.
+        let byte = (color & 0x3) * 0x55;
+        let frame_buffer = self.get_frame_buffer();
+        unsafe {
+            frame_buffer.write_bytes(byte, BANK_SIZE);
+            frame_buffer
+                .add(ODD_BANK_OFFSET)
+                .write_bytes(byte, BANK_SIZE);
+        }
+    }
+
+    /// The line is clipped to the screen, so `start` and `end` may lie
+    /// anywhere on the plane.
+    fn draw_line(&self, start: impl Into<Point>, end: impl Into<Point>, color: u8) {
+        let screen = Rect::new(0, 0, WIDTH, HEIGHT);
+        if let Some((start, end)) = clip_line(start, end, &screen) {
+            for (x, y) in Bresenham::new(start, end) {
+                self.set_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+
+    /// Pixels outside of the screen are ignored.
+    fn set_pixel(&self, x: usize, y: usize, color: u8) {
+        if x >= WIDTH || y >= HEIGHT {
+            return;
+        }
+
+        let (offset, shift) = Self::pixel_offset(x, y);
+        unsafe {
+            let pixel = self.get_frame_buffer().add(offset);
+            let byte = pixel.read_volatile() & !(0x3 << shift);
+            pixel.write_volatile(byte | (color & 0x3) << shift);
+        }
+    }
+
+    /// Any part of the character that falls outside of the screen is clipped.
+    fn draw_character(&self, x: usize, y: usize, character: char, color: u8) {
+        if x >= WIDTH || y >= HEIGHT {
+            return;
+        }
+
+        let character = basic_glyph(character);
+
+        for (row, byte) in character.iter().enumerate() {
+            for bit in 0..8 {
+                match *byte & 1 << bit {
+                    0 => (),
+                    _ => self.set_pixel(x + bit, y + row, color),
+                }
+            }
+        }
+    }
+
+    fn set_mode(&self) {
+        let mut vga = VGA.lock();
+        vga.set_video_mode(VideoMode::Mode320x200x4);
+
+        // Some bios mess up the palette when switching modes,
+        // so explicitly set it.
+        vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+    }
+
+    unsafe fn frame_buffer_ptr(&self) -> *mut u8 {
+        FrameBuffer::CgaMode.as_ptr()
+    }
+}
+
+impl Graphics320x200x4 {
+    /// Creates a new `Graphics320x200x4`.
+    pub const fn new() -> Graphics320x200x4 {
+        Graphics320x200x4
+    }
+
+    /// Returns the color of the pixel at `(x, y)`, or `None` if
+    /// `(x, y)` is outside of the screen.
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<u8> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+
+        let (offset, shift) = Self::pixel_offset(x, y);
+        let byte = unsafe { self.get_frame_buffer().add(offset).read_volatile() };
+        Some((byte >> shift) & 0x3)
+    }
+
+    /// Returns the offset of the byte holding the pixel at `(x, y)` and
+    /// the shift of its 2 bits within that byte.
+    #[inline]
+    fn pixel_offset(x: usize, y: usize) -> (usize, usize) {
+        let bank = (y & 1) * ODD_BANK_OFFSET;
+        let offset = bank + (y / 2) * WIDTH_IN_BYTES + x / 4;
+        (offset, 6 - (x & 0x3) * 2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pixel_offset() {
+        assert_eq!(Graphics320x200x4::pixel_offset(0, 0), (0, 6));
+        assert_eq!(Graphics320x200x4::pixel_offset(3, 0), (0, 0));
+        assert_eq!(Graphics320x200x4::pixel_offset(4, 0), (1, 6));
+        assert_eq!(Graphics320x200x4::pixel_offset(0, 1), (0x2000, 6));
+        assert_eq!(Graphics320x200x4::pixel_offset(5, 2), (81, 4));
+        assert_eq!(
+            Graphics320x200x4::pixel_offset(WIDTH - 1, HEIGHT - 1),
+            (0x2000 + BANK_SIZE - 1, 0)
+        );
+    }
+}
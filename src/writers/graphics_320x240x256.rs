@@ -1,15 +1,16 @@
 use super::{GraphicsWriter, Screen};
 use crate::{
     colors::DEFAULT_PALETTE,
-    drawing::{Bresenham, Point},
-    registers::PlaneMask,
-    vga::{VideoMode, VGA},
+    drawing::{clip_line, Bresenham, Point, Rect},
+    fonts::basic_glyph,
+    registers::{CrtcControllerIndex, PlaneMask},
+    vga::{FrameBuffer, VideoMode, VGA},
 };
-use font8x8::UnicodeFonts;
 
 const WIDTH: usize = 320;
 const HEIGHT: usize = 240;
 const SIZE: usize = (WIDTH * HEIGHT) / 4;
+const PAGE_COUNT: u8 = 3;
 
 /// A basic interface for interacting with vga graphics mode 320x200x256.
 ///
@@ -33,8 +34,15 @@ const SIZE: usize = (WIDTH * HEIGHT) / 4;
 ///     mode.draw_character(118 + offset * 8, 27, character, 255);
 /// }
 /// ```
+///
+/// Since each plane holds a quarter of the pixels, a full screen only takes
+/// `320 * 240 / 4` bytes of each plane's 64KB, which leaves room for
+/// `Graphics320x240x256::PAGE_COUNT` pages. Drawing can happen on a hidden page
+/// while another is displayed, see `Graphics320x240x256::page_flip`.
 #[derive(Debug, Clone, Copy, Default)]
-pub struct Graphics320x240x256;
+pub struct Graphics320x240x256 {
+    active_page: u8,
+}
 
 impl Screen for Graphics320x240x256 {
     const WIDTH: usize = WIDTH;
@@ -44,7 +52,7 @@ impl Screen for Graphics320x240x256 {
 
 impl GraphicsWriter<u8> for Graphics320x240x256 {
     fn clear_screen(&self, color: u8) {
-        let frame_buffer = self.get_frame_buffer();
+        let frame_buffer = self.get_page_buffer();
         VGA.lock()
             .sequencer_registers
             .set_plane_mask(PlaneMask::ALL_PLANES);
@@ -52,13 +60,18 @@ impl GraphicsWriter<u8> for Graphics320x240x256 {
             frame_buffer.write_bytes(color, Self::SIZE);
         }
     }
-    fn draw_line(&self, start: Point<isize>, end: Point<isize>, color: u8) {
-        for (x, y) in Bresenham::new(start, end) {
-            self.set_pixel(x as usize, y as usize, color);
+    /// The line is clipped to the screen, so `start` and `end` may lie
+    /// anywhere on the plane.
+    fn draw_line(&self, start: impl Into<Point>, end: impl Into<Point>, color: u8) {
+        let screen = Rect::new(0, 0, WIDTH, HEIGHT);
+        if let Some((start, end)) = clip_line(start, end, &screen) {
+            for (x, y) in Bresenham::new(start, end) {
+                self.set_pixel(x as usize, y as usize, color);
+            }
         }
     }
     fn set_pixel(&self, x: usize, y: usize, color: u8) {
-        let frame_buffer = self.get_frame_buffer();
+        let frame_buffer = self.get_page_buffer();
         unsafe {
             let offset = (WIDTH * y + x) / 4;
             let plane_mask = 0x1 << (x & 3);
@@ -69,11 +82,7 @@ impl GraphicsWriter<u8> for Graphics320x240x256 {
         }
     }
     fn draw_character(&self, x: usize, y: usize, character: char, color: u8) {
-        let character = match font8x8::BASIC_FONTS.get(character) {
-            Some(character) => character,
-            // Default to a filled block if the character isn't found
-            None => font8x8::unicode::BLOCK_UNICODE[8].byte_array(),
-        };
+        let character = basic_glyph(character);
 
         for (row, byte) in character.iter().enumerate() {
             for bit in 0..8 {
@@ -92,11 +101,83 @@ impl GraphicsWriter<u8> for Graphics320x240x256 {
         // so explicitly set it.
         vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
     }
+
+    /// Returns the start of the active page.
+    unsafe fn frame_buffer_ptr(&self) -> *mut u8 {
+        FrameBuffer::GraphicsMode
+            .as_ptr()
+            .add(usize::from(self.active_page) * SIZE)
+    }
 }
 
 impl Graphics320x240x256 {
+    /// The number of pages that fit in video memory.
+    pub const PAGE_COUNT: u8 = PAGE_COUNT;
+
     /// Creates a new `Graphics320x240x256`.
     pub const fn new() -> Graphics320x240x256 {
-        Graphics320x240x256
+        Graphics320x240x256 { active_page: 0 }
+    }
+
+    /// Returns the page drawing operations write to.
+    pub fn get_active_page(&self) -> u8 {
+        self.active_page
+    }
+
+    /// Sets the page drawing operations write to, which doesn't
+    /// need to be the page that's displayed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` isn't less than `Graphics320x240x256::PAGE_COUNT`.
+    pub fn set_active_page(&mut self, page: u8) {
+        assert!(page < PAGE_COUNT, "page {} doesn't exist", page);
+        self.active_page = page;
+    }
+
+    /// Displays `page` by pointing the crtc start address registers at it.
+    ///
+    /// The vga picks up the new start address at the next vertical retrace, so
+    /// call `Vga::wait_for_vsync` before drawing to the previously displayed page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` isn't less than `Graphics320x240x256::PAGE_COUNT`.
+    pub fn show_page(&mut self, page: u8) {
+        assert!(page < PAGE_COUNT, "page {} doesn't exist", page);
+        let start_address = usize::from(page) * SIZE;
+        let mut vga = VGA.lock();
+        let emulation_mode = vga.get_emulation_mode();
+        vga.crtc_controller_registers.write(
+            emulation_mode,
+            CrtcControllerIndex::StartAddressHigh,
+            (start_address >> 8) as u8,
+        );
+        vga.crtc_controller_registers.write(
+            emulation_mode,
+            CrtcControllerIndex::StartAddressLow,
+            (start_address & 0xFF) as u8,
+        );
+    }
+
+    /// Displays the active page and makes the next page active, so the next
+    /// frame can be drawn without being visible.
+    ///
+    /// Drawing starts out on the displayed page, so the first flip only
+    /// switches drawing to a hidden page. This waits for the vertical retrace
+    /// that picks up the new start address before returning, so the newly
+    /// hidden page isn't drawn to while it's still visible.
+    pub fn page_flip(&mut self) {
+        self.show_page(self.active_page);
+        VGA.lock().wait_for_vsync();
+        self.active_page = (self.active_page + 1) % PAGE_COUNT;
+    }
+
+    /// Returns the start of the active page.
+    fn get_page_buffer(&self) -> *mut u8 {
+        unsafe {
+            self.get_frame_buffer()
+                .add(usize::from(self.active_page) * SIZE)
+        }
     }
 }
@@ -0,0 +1,122 @@
+use super::{Graphics640x480x16, GraphicsWriter, Screen};
+use crate::colors::Color16;
+use core::fmt;
+
+/// The size of a character cell in pixels.
+const CELL_SIZE: usize = 8;
+
+/// A cursor based writer for `Graphics640x480x16` that implements
+/// `core::fmt::Write`, laying text out on a grid of 8x8 pixel cells.
+///
+/// Characters are drawn over their cell's background at the current cursor
+/// position, which advances after each character. A `\n` moves the cursor to
+/// the start of the next row and the screen is scrolled up by one row of
+/// cells once the cursor moves past the last row.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use core::fmt::Write;
+/// use vga::colors::Color16;
+/// use vga::writers::{Graphics640x480x16, GraphicsTextWriter, GraphicsWriter};
+///
+/// let mode = Graphics640x480x16::new();
+/// mode.set_mode();
+/// mode.clear_screen(Color16::Black);
+///
+/// let mut writer = GraphicsTextWriter::new(mode, Color16::Yellow, Color16::Black);
+/// writeln!(writer, "Hello World!").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphicsTextWriter {
+    mode: Graphics640x480x16,
+    foreground: Color16,
+    background: Color16,
+    column: usize,
+    row: usize,
+}
+
+impl GraphicsTextWriter {
+    /// The number of character cells in each row.
+    pub const COLUMNS: usize = <Graphics640x480x16 as Screen>::WIDTH / CELL_SIZE;
+    /// The number of rows of character cells.
+    pub const ROWS: usize = <Graphics640x480x16 as Screen>::HEIGHT / CELL_SIZE;
+
+    /// Creates a new `GraphicsTextWriter` for the given `mode`, drawing
+    /// characters in `foreground` over `background` starting at `(0, 0)`.
+    pub const fn new(
+        mode: Graphics640x480x16,
+        foreground: Color16,
+        background: Color16,
+    ) -> GraphicsTextWriter {
+        GraphicsTextWriter {
+            mode,
+            foreground,
+            background,
+            column: 0,
+            row: 0,
+        }
+    }
+
+    /// Returns the current cursor position as `(column, row)`, in cells.
+    pub fn get_position(&self) -> (usize, usize) {
+        (self.column, self.row)
+    }
+
+    /// Moves the cursor to `(column, row)`, clamped to the size of the screen.
+    pub fn set_position(&mut self, column: usize, row: usize) {
+        self.column = column.min(Self::COLUMNS - 1);
+        self.row = row.min(Self::ROWS - 1);
+    }
+
+    /// Returns the `(foreground, background)` colors used for newly
+    /// written characters.
+    pub fn get_colors(&self) -> (Color16, Color16) {
+        (self.foreground, self.background)
+    }
+
+    /// Sets the `foreground` and `background` colors used for newly
+    /// written characters.
+    pub fn set_colors(&mut self, foreground: Color16, background: Color16) {
+        self.foreground = foreground;
+        self.background = background;
+    }
+
+    /// Writes `character` at the current cursor position and advances the cursor.
+    pub fn put_char(&mut self, character: char) {
+        match character {
+            '\n' => self.new_line(),
+            '\r' => self.column = 0,
+            character => {
+                if self.column >= Self::COLUMNS {
+                    self.new_line();
+                }
+                let (x, y) = (self.column * CELL_SIZE, self.row * CELL_SIZE);
+                self.mode
+                    .fill_rect((x, y), CELL_SIZE, CELL_SIZE, self.background);
+                self.mode.draw_character(x, y, character, self.foreground);
+                self.column += 1;
+            }
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.column = 0;
+        if self.row + 1 < Self::ROWS {
+            self.row += 1;
+        } else {
+            self.mode.scroll_up(CELL_SIZE, self.background);
+        }
+    }
+}
+
+impl fmt::Write for GraphicsTextWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars() {
+            self.put_char(character);
+        }
+        Ok(())
+    }
+}
@@ -1,28 +1,40 @@
 //! Writers for common vga modes.
+mod double_buffered_640x480x16;
 mod graphics_320x200x256;
+mod graphics_320x200x4;
 mod graphics_320x240x256;
-mod graphics_640x480x16;
+mod graphics_text_writer;
+mod planar_graphics;
+mod surface;
 mod text_40x25;
 mod text_40x50;
 mod text_80x25;
+mod text_80x50;
+mod text_mode_writer;
 
 use super::{
-    colors::{Color16, TextModeColor},
-    drawing::Point,
-    registers::CrtcControllerIndex,
-    vga::{Vga, VGA},
+    colors::TextModeColor,
+    drawing::{Point, Rect},
+    registers::{AttributeControllerIndex, CrtcControllerIndex},
+    vga::{FrameBuffer, Vga, VGA},
 };
 use spinning_top::SpinlockGuard;
 
+pub use double_buffered_640x480x16::DoubleBuffered640x480x16;
 pub use graphics_320x200x256::Graphics320x200x256;
+pub use graphics_320x200x4::Graphics320x200x4;
 pub use graphics_320x240x256::Graphics320x240x256;
-pub use graphics_640x480x16::Graphics640x480x16;
+pub use graphics_text_writer::GraphicsTextWriter;
+pub use planar_graphics::{FrameBufferGuard, Graphics640x480x16, PlanarGraphics, RegionBuffer};
+pub use surface::{OffscreenSurface, Surface};
 pub use text_40x25::Text40x25;
 pub use text_40x50::Text40x50;
 pub use text_80x25::Text80x25;
+pub use text_80x50::Text80x50;
+pub use text_mode_writer::TextModeWriter;
 
 /// Represents a `ScreenCharacter` in vga text modes.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct ScreenCharacter {
     character: u8,
@@ -46,9 +58,18 @@ impl ScreenCharacter {
     }
 }
 
+/// The error returned when a coordinate falls outside of a `Screen`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OutOfBounds {
+    /// The x coordinate that was requested.
+    pub x: usize,
+    /// The y coordinate that was requested.
+    pub y: usize,
+}
+
 static BLANK_CHARACTER: ScreenCharacter = ScreenCharacter {
     character: b' ',
-    color: TextModeColor::new(Color16::Yellow, Color16::Black),
+    color: TextModeColor::YELLOW_ON_BLACK,
 };
 
 /// A helper trait used to interact with various vga screens.
@@ -73,7 +94,7 @@ pub trait TextWriter: Screen {
     fn get_frame_buffer(&self) -> (SpinlockGuard<Vga>, *mut ScreenCharacter) {
         let mut vga = VGA.lock();
         let frame_buffer = vga.get_frame_buffer();
-        (vga, u32::from(frame_buffer) as *mut ScreenCharacter)
+        (vga, frame_buffer.as_ptr() as *mut ScreenCharacter)
     }
 
     /// Clears the screen by setting all cells to `b' '` with
@@ -93,6 +114,48 @@ pub trait TextWriter: Screen {
         }
     }
 
+    /// Fills the block of cells starting at `top_left` with the given `width`
+    /// and `height` with `character`. Any part of the block that falls outside
+    /// of the screen is clipped.
+    fn fill(
+        &self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        character: ScreenCharacter,
+    ) {
+        let screen = Rect::new(0, 0, Self::WIDTH, Self::HEIGHT);
+        let block = Rect::from_point(top_left, width, height).intersection(&screen);
+        let (_vga, frame_buffer) = self.get_frame_buffer();
+        for row in block.rows() {
+            for column in block.columns() {
+                unsafe {
+                    frame_buffer
+                        .add(Self::WIDTH * row + column)
+                        .write_volatile(character);
+                }
+            }
+        }
+    }
+
+    /// Scrolls the screen up by one row, discarding the top row and clearing
+    /// the bottom row to `b' '` with a background color of `Color16::Black`
+    /// and a foreground color of `Color16::Yellow`.
+    fn scroll_up(&self) {
+        let (_vga, frame_buffer) = self.get_frame_buffer();
+        for i in Self::WIDTH..Self::SIZE {
+            unsafe {
+                let character = frame_buffer.add(i).read_volatile();
+                frame_buffer.add(i - Self::WIDTH).write_volatile(character);
+            }
+        }
+        for i in (Self::SIZE - Self::WIDTH)..Self::SIZE {
+            unsafe {
+                frame_buffer.add(i).write_volatile(BLANK_CHARACTER);
+            }
+        }
+    }
+
     /// Disables the cursor in vga text modes.
     fn disable_cursor(&self) {
         let (mut vga, _frame_buffer) = self.get_frame_buffer();
@@ -121,8 +184,36 @@ pub trait TextWriter: Screen {
         );
     }
 
+    /// Makes bit 7 of each attribute byte blink the character instead of
+    /// selecting a bright background, limiting the background to the first
+    /// 8 colors. This is the default after setting a text mode.
+    ///
+    /// See `TextModeColor::set_blink` for how the two modes interact.
+    fn blink_enable(&self) {
+        let (mut vga, _frame_buffer) = self.get_frame_buffer();
+        set_blink(&mut vga, true);
+    }
+
+    /// Makes bit 7 of each attribute byte select a bright background instead
+    /// of blinking the character, allowing all 16 background colors.
+    ///
+    /// See `TextModeColor::set_blink` for how the two modes interact.
+    fn blink_disable(&self) {
+        let (mut vga, _frame_buffer) = self.get_frame_buffer();
+        set_blink(&mut vga, false);
+    }
+
     /// Returns the `ScreenCharacter` at the given `(x, y)` position.
+    ///
+    /// `(x, y)` is not bounds checked in release builds, use
+    /// `try_read_character` if the position may fall outside of the screen.
     fn read_character(&self, x: usize, y: usize) -> ScreenCharacter {
+        debug_assert!(
+            x < Self::WIDTH && y < Self::HEIGHT,
+            "({}, {}) is off screen",
+            x,
+            y
+        );
         let (_vga, frame_buffer) = self.get_frame_buffer();
         let offset = Self::WIDTH * y + x;
         unsafe { frame_buffer.add(offset).read_volatile() }
@@ -177,14 +268,85 @@ pub trait TextWriter: Screen {
         );
     }
 
+    /// Returns the position of the text cursor as `(x, y)`, as read
+    /// back from the cursor location registers.
+    fn get_cursor_position(&self) -> (usize, usize) {
+        let (mut vga, _frame_buffer) = self.get_frame_buffer();
+        let emulation_mode = vga.get_emulation_mode();
+        let cursor_low = vga
+            .crtc_controller_registers
+            .read(emulation_mode, CrtcControllerIndex::TextCursorLocationLow);
+        let cursor_high = vga
+            .crtc_controller_registers
+            .read(emulation_mode, CrtcControllerIndex::TextCursorLocationHigh);
+        let offset = usize::from(cursor_high) << 8 | usize::from(cursor_low);
+        (offset % Self::WIDTH, offset / Self::WIDTH)
+    }
+
+    /// Returns the `ScreenCharacter` at the given `(x, y)` position, or
+    /// `OutOfBounds` if `(x, y)` is outside of the screen.
+    fn try_read_character(&self, x: usize, y: usize) -> Result<ScreenCharacter, OutOfBounds> {
+        if x >= Self::WIDTH || y >= Self::HEIGHT {
+            return Err(OutOfBounds { x, y });
+        }
+        Ok(self.read_character(x, y))
+    }
+
     /// Prints the given `character` and `color` at `(x, y)`.
+    ///
+    /// `(x, y)` is not bounds checked in release builds, use
+    /// `try_write_character` if the position may fall outside of the screen.
     fn write_character(&self, x: usize, y: usize, screen_character: ScreenCharacter) {
+        debug_assert!(
+            x < Self::WIDTH && y < Self::HEIGHT,
+            "({}, {}) is off screen",
+            x,
+            y
+        );
         let (_vga, frame_buffer) = self.get_frame_buffer();
         let offset = Self::WIDTH * y + x;
         unsafe {
             frame_buffer.add(offset).write_volatile(screen_character);
         }
     }
+
+    /// Prints the given `character` and `color` at `(x, y)`, returning
+    /// `OutOfBounds` instead of writing if `(x, y)` is outside of the screen.
+    fn try_write_character(
+        &self,
+        x: usize,
+        y: usize,
+        screen_character: ScreenCharacter,
+    ) -> Result<(), OutOfBounds> {
+        if x >= Self::WIDTH || y >= Self::HEIGHT {
+            return Err(OutOfBounds { x, y });
+        }
+        self.write_character(x, y, screen_character);
+        Ok(())
+    }
+}
+
+/// Sets or clears the blink enable bit `(0x08)` of the attribute
+/// controller's mode control register.
+fn set_blink(vga: &mut Vga, blink: bool) {
+    let emulation_mode = vga.get_emulation_mode();
+    let mode_control = vga
+        .attribute_controller_registers
+        .read(emulation_mode, AttributeControllerIndex::ModeControl);
+    let mode_control = if blink {
+        mode_control | 0x08
+    } else {
+        mode_control & !0x08
+    };
+    vga.attribute_controller_registers.write(
+        emulation_mode,
+        AttributeControllerIndex::ModeControl,
+        mode_control,
+    );
+    // Accessing the attribute controller clears the palette address
+    // source bit, which blanks the screen.
+    vga.attribute_controller_registers
+        .unblank_screen(emulation_mode);
 }
 
 /// A helper trait used to interact with various vga graphics modes.
@@ -192,7 +354,7 @@ pub trait GraphicsWriter<Color> {
     /// Clears the screen by setting all pixels to the specified `color`.
     fn clear_screen(&self, color: Color);
     /// Draws a line from `start` to `end` with the specified `color`.
-    fn draw_line(&self, start: Point<isize>, end: Point<isize>, color: Color);
+    fn draw_line(&self, start: impl Into<Point>, end: impl Into<Point>, color: Color);
     /// Draws a character at the given `(x, y)` coordinant to the specified `color`.
     fn draw_character(&self, x: usize, y: usize, character: char, color: Color);
     /// Sets the given pixel at `(x, y)` to the given `color`.
@@ -201,6 +363,33 @@ pub trait GraphicsWriter<Color> {
     fn set_mode(&self);
     /// Returns the frame buffer for this vga mode.
     fn get_frame_buffer(&self) -> *mut u8 {
-        u32::from(VGA.lock().get_frame_buffer()) as *mut u8
+        VGA.lock().get_frame_buffer().as_ptr()
+    }
+    /// Returns the frame buffer for this vga mode without locking the vga
+    /// driver like `get_frame_buffer` does.
+    ///
+    /// # Safety
+    ///
+    /// The vga must be in the mode of this writer, and the caller has to
+    /// coordinate every access through the pointer with the lock on `VGA`,
+    /// for example by holding it through `with_locked_vga`, since other
+    /// drawing methods reprogram the registers the frame buffer relies on.
+    unsafe fn frame_buffer_ptr(&self) -> *mut u8 {
+        FrameBuffer::GraphicsMode.as_ptr()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::colors::Color16;
+
+    #[test]
+    fn test_screen_character_layout() {
+        let color = TextModeColor::new(Color16::Yellow, Color16::Blue);
+        let screen_character = ScreenCharacter::new(b'A', color);
+        assert_eq!(core::mem::size_of::<ScreenCharacter>(), 2);
+        let bytes: [u8; 2] = unsafe { core::mem::transmute(screen_character) };
+        assert_eq!(bytes, [b'A', 0x1E]);
     }
 }
@@ -0,0 +1,2621 @@
+use super::{GraphicsWriter, OffscreenSurface, OutOfBounds, Screen, Surface};
+use crate::{
+    colors::{nearest_color16, Color16, COLOR16_RGB, DEFAULT_PALETTE},
+    dither::{dither_color16, ColorMatching},
+    drawing::{
+        clip_line, gradient_dither, Bezier, Bresenham, Circle, Coordinates, Ellipse,
+        GradientDirection, LineCap, LineStyle, Point, Rect, TextAlignment, ThickLine, WrappedLines,
+    },
+    fonts::{basic_glyph, Font},
+    registers::{PlaneMask, RasterOp, ReadPlane, WriteMode},
+    vga::{Vga, VideoMode, VGA},
+};
+use core::{cell::Cell, ops::Range};
+use spinning_top::SpinlockGuard;
+
+const FLOOD_FILL_CAPACITY: usize = 1024;
+const MAX_POLYGON_VERTICES: usize = 64;
+
+/// A rectangular region of the screen saved by `Graphics640x480x16::save_region`,
+/// which can be put back with `Graphics640x480x16::restore_region`.
+///
+/// The pixels are stored in a caller provided buffer, laid out the same way as
+/// the bitmaps drawn by `Graphics640x480x16::blit_bitmap`.
+#[derive(Debug)]
+pub struct RegionBuffer<'a> {
+    buffer: &'a mut [u8],
+    top_left: Point,
+    width: usize,
+    height: usize,
+}
+
+impl<'a> RegionBuffer<'a> {
+    /// Returns the number of bytes needed to save a region with the given
+    /// `width` and `height`.
+    pub const fn required_size(width: usize, height: usize) -> usize {
+        width.div_ceil(2) * height
+    }
+
+    /// Returns the top left corner of the saved region.
+    pub fn top_left(&self) -> Point {
+        self.top_left
+    }
+
+    /// Returns the width of the saved region, after clipping it to the screen.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the saved region, after clipping it to the screen.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Releases the buffer.
+    pub fn into_inner(self) -> &'a mut [u8] {
+        self.buffer
+    }
+}
+
+/// A basic interface for interacting with vga graphics mode 640x480x16
+///
+/// This is `PlanarGraphics` at 640x480, the resolution of
+/// `VideoMode::Mode640x480x16`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use vga::colors::Color16;
+/// use vga::writers::{Graphics640x480x16, GraphicsWriter};
+
+/// let mode = Graphics640x480x16::new();
+/// mode.set_mode();
+/// mode.clear_screen(Color16::Black);
+/// mode.draw_line((80, 60), (80, 420), Color16::White);
+/// mode.draw_line((80, 60), (540, 60), Color16::White);
+/// mode.draw_line((80, 420), (540, 420), Color16::White);
+/// mode.draw_line((540, 420), (540, 60), Color16::White);
+/// mode.draw_line((80, 90), (540, 90), Color16::White);
+/// for (offset, character) in "Hello World!".chars().enumerate() {
+///     mode.draw_character(270 + offset * 8, 72, character, Color16::White)
+/// }
+/// ```
+pub type Graphics640x480x16 = PlanarGraphics<640, 480>;
+
+/// A writer for the 16 color planar graphics modes, where every pixel takes
+/// one bit in each of the four planes, at a resolution of `WIDTH` by `HEIGHT`.
+///
+/// All drawing works the same at any resolution. `set_mode` programs the
+/// built in modes for 320x200, 640x350 and 640x480, see
+/// `PlanarGraphics::VIDEO_MODE`, and fails to compile for any other
+/// resolution, which needs a `VgaConfiguration` loaded through
+/// `Vga::set_custom_mode` before drawing.
+///
+/// `WIDTH` has to be a multiple of 8 and each plane has to fit in the 64 KiB
+/// graphics frame buffer, which is checked at compile time by `new`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vga::colors::Color16;
+/// use vga::writers::{GraphicsWriter, PlanarGraphics};
+///
+/// let mode = PlanarGraphics::<640, 350>::new();
+/// mode.set_mode();
+/// mode.clear_screen(Color16::Black);
+/// mode.draw_line((0, 0), (639, 349), Color16::White);
+/// ```
+///
+/// Resolutions without a built in mode can't call `set_mode`:
+///
+/// ```compile_fail
+/// use vga::writers::{GraphicsWriter, PlanarGraphics};
+///
+/// PlanarGraphics::<640, 400>::new().set_mode();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlanarGraphics<const WIDTH: usize, const HEIGHT: usize> {
+    clip: Cell<Rect>,
+    color_matching: Cell<ColorMatching>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> Default for PlanarGraphics<WIDTH, HEIGHT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> Screen for PlanarGraphics<WIDTH, HEIGHT> {
+    const WIDTH: usize = WIDTH;
+    const HEIGHT: usize = HEIGHT;
+    const SIZE: usize = (WIDTH * HEIGHT) / 8;
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> GraphicsWriter<Color16>
+    for PlanarGraphics<WIDTH, HEIGHT>
+{
+    /// The screen is always cleared with `RasterOp::Replace`, regardless of
+    /// the current raster op.
+    ///
+    /// `color` is loaded into the set/reset register with set/reset enabled for
+    /// all planes, so each byte written sets 8 pixels in all four planes at once.
+    fn clear_screen(&self, color: Color16) {
+        self.lock().clear_screen(color);
+    }
+
+    /// The line is clipped to the clip rectangle before it's drawn, so `start`
+    /// and `end` may lie anywhere on the plane.
+    fn draw_line(&self, start: impl Into<Point>, end: impl Into<Point>, color: Color16) {
+        self.lock().draw_line(start, end, color);
+    }
+
+    /// Any part of the character that falls outside of the screen or the
+    /// clip rectangle is clipped.
+    fn draw_character(&self, x: usize, y: usize, character: char, color: Color16) {
+        self.lock().draw_character(x, y, character, color);
+    }
+
+    /// **Note:** This method is provided for convenience, but has terrible
+    /// performance since it needs to ensure the correct `WriteMode` per pixel
+    /// drawn. If you need to draw more then one pixel, consider using a method
+    /// such as `draw_line`, or `FrameBufferGuard::set_pixel` through `lock`.
+    ///
+    /// `(x, y)` is not bounds checked in release builds, use `try_set_pixel`
+    /// if the coordinates may fall outside of the screen. Pixels outside of
+    /// the clip rectangle are silently discarded.
+    fn set_pixel(&self, x: usize, y: usize, color: Color16) {
+        self.lock().set_pixel(x, y, color);
+    }
+
+    /// Fails to compile if `PlanarGraphics::VIDEO_MODE` is `None`, as there's
+    /// no built in mode for the resolution.
+    fn set_mode(&self) {
+        let video_mode = const {
+            match Self::VIDEO_MODE {
+                Some(video_mode) => video_mode,
+                None => panic!("no built in mode for this resolution"),
+            }
+        };
+        let mut vga = VGA.lock();
+        vga.set_video_mode(video_mode);
+
+        // Some bios mess up the palette when switching modes,
+        // so explicitly set it.
+        vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> PlanarGraphics<WIDTH, HEIGHT> {
+    /// The number of pending spans `Graphics640x480x16::flood_fill` can keep track of.
+    pub const FLOOD_FILL_CAPACITY: usize = FLOOD_FILL_CAPACITY;
+
+    /// The maximum number of vertices `Graphics640x480x16::fill_polygon` accepts.
+    pub const MAX_POLYGON_VERTICES: usize = MAX_POLYGON_VERTICES;
+
+    /// The size in bytes of the buffer required by `Graphics640x480x16::dump_to`.
+    pub const DUMP_SIZE: usize = (WIDTH * HEIGHT) / 2;
+
+    /// The mode `GraphicsWriter::set_mode` switches to, or `None` if there's
+    /// no built in mode for the resolution.
+    pub const VIDEO_MODE: Option<VideoMode> = match (WIDTH, HEIGHT) {
+        (320, 200) => Some(VideoMode::Mode320x200x16),
+        (640, 350) => Some(VideoMode::Mode640x350x16),
+        (640, 480) => Some(VideoMode::Mode640x480x16),
+        _ => None,
+    };
+
+    const WIDTH_IN_BYTES: usize = WIDTH / 8;
+
+    /// The whole screen, which is also the default clip rectangle.
+    const SCREEN: Rect = {
+        assert!(WIDTH.is_multiple_of(8), "the width must be a multiple of 8");
+        assert!(
+            Self::WIDTH_IN_BYTES * HEIGHT <= 0x10000,
+            "each plane must fit in the 64 KiB frame buffer"
+        );
+        Rect::new(0, 0, WIDTH, HEIGHT)
+    };
+
+    /// Creates a new `PlanarGraphics`.
+    pub const fn new() -> Self {
+        PlanarGraphics {
+            clip: Cell::new(Self::SCREEN),
+            color_matching: Cell::new(ColorMatching::Nearest),
+        }
+    }
+
+    /// Limits all drawing to the rectangle given as `(x, y, width, height)`,
+    /// silently discarding any pixels that fall outside of it. Passing `None`
+    /// restores drawing to the whole screen.
+    ///
+    /// `clear_screen`, `copy_rect` and the scroll methods always operate on
+    /// the whole screen.
+    pub fn set_clip(&self, clip: Option<(usize, usize, usize, usize)>) {
+        self.clip.set(match clip {
+            Some((x, y, width, height)) => {
+                Rect::new(x as isize, y as isize, width, height).intersection(&Self::SCREEN)
+            }
+            None => Self::SCREEN,
+        });
+    }
+
+    /// Returns how `set_pixel_rgb` turns 24 bit colors into a `Color16`.
+    pub fn color_matching(&self) -> ColorMatching {
+        self.color_matching.get()
+    }
+
+    /// Sets how `set_pixel_rgb` turns 24 bit colors into a `Color16`,
+    /// which is `ColorMatching::Nearest` by default.
+    pub fn set_color_matching(&self, color_matching: ColorMatching) {
+        self.color_matching.set(color_matching);
+    }
+
+    /// Sets the pixel at `(x, y)` to the 24 bit color given by `red`, `green`
+    /// and `blue`, matched to a `Color16` as chosen by `set_color_matching`.
+    ///
+    /// Like `set_pixel`, `(x, y)` is not bounds checked in release builds and
+    /// pixels outside of the clip rectangle are silently discarded.
+    pub fn set_pixel_rgb(&self, x: usize, y: usize, red: u8, green: u8, blue: u8) {
+        let color = self.color_matching.get().color16(x, y, (red, green, blue));
+        self.set_pixel(x, y, color);
+    }
+
+    /// Locks the vga driver until the returned `FrameBufferGuard` is dropped,
+    /// letting a batch of drawing operations share a single lock.
+    ///
+    /// Every other method of `Graphics640x480x16` also locks the vga driver,
+    /// so calling them while the guard is alive deadlocks.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use vga::colors::Color16;
+    /// use vga::writers::{Graphics640x480x16, GraphicsWriter};
+    ///
+    /// let mode = Graphics640x480x16::new();
+    /// mode.set_mode();
+    ///
+    /// let mut guard = mode.lock();
+    /// guard.clear_screen(Color16::Black);
+    /// guard.fill_rect((80, 60), 460, 360, Color16::Blue);
+    /// guard.draw_line((80, 60), (540, 420), Color16::White);
+    /// ```
+    pub fn lock(&self) -> FrameBufferGuard<'_, WIDTH, HEIGHT> {
+        let mut vga = VGA.lock();
+        let frame_buffer = vga.get_frame_buffer().as_ptr();
+        FrameBufferGuard {
+            mode: self,
+            vga,
+            frame_buffer,
+            write_mode_2: false,
+        }
+    }
+
+    /// Returns the rectangle drawing is currently limited to, which is the
+    /// whole screen unless changed by `set_clip`.
+    pub fn get_clip(&self) -> Rect {
+        self.clip.get()
+    }
+
+    /// Draws `string` starting at `(x, y)` with the specified `color`, advancing
+    /// 8 pixels per character. Text wraps back to `x` on the next line when it
+    /// reaches the right edge of the screen or on `\n`, and anything that falls
+    /// below the bottom of the screen is not drawn.
+    pub fn draw_string(&self, x: usize, y: usize, string: &str, color: Color16) {
+        Self::layout_string(x, y, string, 8, 8, |column, row, character| {
+            self.draw_character(column, row, character, color)
+        });
+    }
+
+    /// Draws `string` like `draw_string`, with every pixel of the 8x8 font
+    /// drawn as a `scale` by `scale` block, advancing `8 * scale` pixels per
+    /// character and line. Any part of a character that falls outside of the
+    /// screen or the clip rectangle is clipped.
+    pub fn draw_string_scaled(
+        &self,
+        x: usize,
+        y: usize,
+        string: &str,
+        color: Color16,
+        scale: usize,
+    ) {
+        if scale == 0 {
+            return;
+        }
+        let mut guard = self.lock();
+        let size = 8 * scale;
+        Self::layout_string(x, y, string, size, size, |column, row, character| {
+            guard.draw_character_scaled(column, row, character, color, scale)
+        });
+    }
+
+    /// Draws `text` within `rect`, 8 pixels per character, breaking lines at
+    /// each `\n` and wrapping words that don't fit on the current line. Each
+    /// line is placed according to `alignment`, and words longer than a whole
+    /// line are broken wherever the line is full.
+    ///
+    /// Nothing is drawn outside of `rect`, so a last line that only partly
+    /// fits is cut off at its bottom.
+    pub fn draw_string_wrapped(
+        &self,
+        rect: Rect,
+        text: &str,
+        color: Color16,
+        alignment: TextAlignment,
+    ) {
+        let clip = rect.intersection(&self.clip.get());
+        if clip.is_empty() {
+            return;
+        }
+        let mut guard = self.lock();
+        let lines = WrappedLines::new(text, rect.width / 8);
+        for (line, y) in lines.zip((rect.y..clip.bottom()).step_by(8)) {
+            let x = rect.x + alignment.offset(rect.width, line.chars().count() * 8) as isize;
+            for (i, character) in line.chars().enumerate() {
+                guard.draw_character_clipped(x + i as isize * 8, y, character, color, &clip);
+            }
+        }
+    }
+
+    /// Draws a character at the given `(x, y)` coordinate to the specified `color`
+    /// using the given `font`. Any part of the character that falls outside of
+    /// the screen is clipped.
+    pub fn draw_character_with_font<F: Font + ?Sized>(
+        &self,
+        x: usize,
+        y: usize,
+        character: char,
+        color: Color16,
+        font: &F,
+    ) {
+        self.set_write_mode_2();
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+
+        for (row, byte) in font.glyph(character).iter().enumerate() {
+            for bit in 0..8 {
+                match *byte & 0x80 >> bit {
+                    0 => (),
+                    _ => self.write_clipped_pixel(
+                        &mut vga,
+                        frame_buffer,
+                        (x + bit) as isize,
+                        (y + row) as isize,
+                        color,
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Draws `string` with the given `font`, laid out the same way as `draw_string`
+    /// with each line advancing by the height of the `font`.
+    pub fn draw_string_with_font<F: Font + ?Sized>(
+        &self,
+        x: usize,
+        y: usize,
+        string: &str,
+        color: Color16,
+        font: &F,
+    ) {
+        Self::layout_string(x, y, string, 8, font.height(), |column, row, character| {
+            self.draw_character_with_font(column, row, character, color, font)
+        });
+    }
+
+    /// Draws the 24 bit `pixels` of a `width` by `height` image, stored row by row,
+    /// with its top left corner at `origin`. Each pixel is mapped to the nearest
+    /// `Color16`, using Floyd-Steinberg dithering to spread the difference onto
+    /// the neighbouring pixels. Any part of the image that falls outside of the
+    /// screen is clipped.
+    ///
+    /// `scratch` holds the accumulated error of the current and next rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` has fewer than `width * height` entries or `scratch`
+    /// has fewer than `2 * (width + 2)` entries.
+    pub fn draw_rgb_image_dithered(
+        &self,
+        origin: impl Into<Point>,
+        width: usize,
+        height: usize,
+        pixels: &[(u8, u8, u8)],
+        scratch: &mut [[i16; 3]],
+    ) {
+        let origin = origin.into();
+        assert!(pixels.len() >= width * height, "not enough pixels");
+        assert!(scratch.len() >= 2 * (width + 2), "scratch is too small");
+
+        self.set_write_mode_2();
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        let (mut current, mut next) = scratch[..2 * (width + 2)].split_at_mut(width + 2);
+        for error in current.iter_mut() {
+            *error = [0; 3];
+        }
+
+        for y in 0..height {
+            for error in next.iter_mut() {
+                *error = [0; 3];
+            }
+
+            for x in 0..width {
+                let (red, green, blue) = pixels[y * width + x];
+                let mut value = [0u8; 3];
+                for (i, component) in [red, green, blue].iter().enumerate() {
+                    let component = i16::from(*component) + current[x + 1][i] / 16;
+                    value[i] = component.clamp(0, 255) as u8;
+                }
+
+                let color = nearest_color16(value[0], value[1], value[2]);
+                let (r, g, b) = COLOR16_RGB[usize::from(u8::from(color))];
+                for (i, component) in [r, g, b].iter().enumerate() {
+                    // Errors are stored scaled by 16 to keep the fractional weights.
+                    let error = i16::from(value[i]) - i16::from(*component);
+                    current[x + 2][i] += error * 7;
+                    next[x][i] += error * 3;
+                    next[x + 1][i] += error * 5;
+                    next[x + 2][i] += error;
+                }
+
+                self.write_clipped_pixel(
+                    &mut vga,
+                    frame_buffer,
+                    origin.x + x as isize,
+                    origin.y + y as isize,
+                    color,
+                );
+            }
+
+            core::mem::swap(&mut current, &mut next);
+        }
+    }
+
+    /// Draws the 24 bit `pixels` of a `width` by `height` image, stored row by row,
+    /// with its top left corner at `origin`. Each pixel is mapped to a `Color16`
+    /// with ordered dithering, see `dither::dither_color16`. Any part of the image
+    /// that falls outside of the screen is clipped.
+    ///
+    /// This is cheaper than `draw_rgb_image_dithered` and needs no scratch buffer,
+    /// at the cost of a visible cross hatch pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` has fewer than `width * height` entries.
+    pub fn draw_rgb_image_ordered(
+        &self,
+        origin: impl Into<Point>,
+        width: usize,
+        height: usize,
+        pixels: &[(u8, u8, u8)],
+    ) {
+        assert!(pixels.len() >= width * height, "not enough pixels");
+
+        let origin = origin.into();
+        self.draw_pixels((0..height).flat_map(|y| {
+            (0..width).map(move |x| {
+                let (screen_x, screen_y) = (origin.x + x as isize, origin.y + y as isize);
+                // Pixels off the screen are skipped, so their dithering doesn't matter.
+                let color =
+                    dither_color16(screen_x as usize, screen_y as usize, pixels[y * width + x]);
+                ((screen_x, screen_y), color)
+            })
+        }));
+    }
+
+    /// Writes the 8 pixels of the byte at column `byte_x` of row `y`, which
+    /// start at pixel `(byte_x * 8, y)`. Bit `7 - i` of `planes[p]` is bit `p`
+    /// of the color of pixel `i`, so each byte holds one plane like in video
+    /// memory.
+    ///
+    /// Each plane takes a single write, and the pixels are only read first when
+    /// the raster op isn't `RasterOp::Replace`. This is meant as a building
+    /// block for custom blitters, so the clip rectangle is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_x` is at least `WIDTH / 8` or `y` is at least `HEIGHT`.
+    pub fn write_byte_column(&self, byte_x: usize, y: usize, planes: [u8; 4]) {
+        assert!(
+            byte_x < Self::WIDTH_IN_BYTES && y < HEIGHT,
+            "byte column is outside of the screen"
+        );
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode0);
+        vga.graphics_controller_registers
+            .write_enable_set_reset(0x0);
+        vga.graphics_controller_registers.set_bit_mask(0xFF);
+        let replace = vga.graphics_controller_registers.get_raster_op() == RasterOp::Replace;
+
+        let offset = byte_x + y * Self::WIDTH_IN_BYTES;
+        for (plane, byte) in PlaneMask::ALL_PLANES.iter().zip(planes.iter()) {
+            vga.sequencer_registers
+                .set_plane_mask(PlaneMask::from_planes(&[plane]));
+            unsafe {
+                if !replace {
+                    frame_buffer.add(offset).read_volatile();
+                }
+                frame_buffer.add(offset).write_volatile(*byte);
+            }
+        }
+
+        vga.sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+    }
+
+    /// Draws the `width` by `height` bitmap in `data` with its top left corner at
+    /// `origin`. Any part of the bitmap that falls outside of the screen is clipped.
+    ///
+    /// `data` is packed as 4 bits per pixel with the even pixel of each pair in the
+    /// high nibble, and each row starts on a new byte, so a row takes `(width + 1) / 2`
+    /// bytes. For bitmaps with an even width, this matches the layout of the back
+    /// buffer used by `DoubleBuffered640x480x16`.
+    ///
+    /// The bitmap is written one plane at a time, 8 pixels per byte, with the bit
+    /// mask limiting the ragged left and right edges to the pixels it covers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` has fewer than `(width + 1) / 2 * height` bytes.
+    pub fn blit_bitmap(&self, origin: impl Into<Point>, width: usize, height: usize, data: &[u8]) {
+        self.blit(origin.into(), width, height, data, None);
+    }
+
+    /// Draws the `width` by `height` bitmap in `data` the same way as `blit_bitmap`,
+    /// except that pixels equal to `transparent` are skipped, leaving whatever is
+    /// underneath them untouched.
+    ///
+    /// The opaque pixels of each byte are collected into the bit mask, so only
+    /// their bits are modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` has fewer than `(width + 1) / 2 * height` bytes.
+    pub fn blit_bitmap_masked(
+        &self,
+        origin: impl Into<Point>,
+        width: usize,
+        height: usize,
+        data: &[u8],
+        transparent: Color16,
+    ) {
+        self.blit(origin.into(), width, height, data, Some(transparent));
+    }
+
+    /// Sets the `RasterOp` used by subsequent drawing operations. With
+    /// `RasterOp::Xor`, drawing the same shape twice restores what was
+    /// underneath it.
+    ///
+    /// `clear_screen` and `copy_rect` always use `RasterOp::Replace`.
+    pub fn set_raster_op(&self, raster_op: RasterOp) {
+        VGA.lock()
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+    }
+
+    /// Returns the `RasterOp` used by drawing operations.
+    pub fn get_raster_op(&self) -> RasterOp {
+        VGA.lock().graphics_controller_registers.get_raster_op()
+    }
+
+    /// Clears `rect` to `Color16::Black`, like `clear_screen` does for the
+    /// whole screen. Any part of `rect` that falls outside of the screen or
+    /// the clip rectangle is clipped.
+    ///
+    /// Black comes straight from the set/reset register with `RasterOp::Replace`,
+    /// whatever the current raster op is, so erasing a widget before redrawing
+    /// it costs one write per byte for all four planes.
+    pub fn clear_rect(&self, rect: impl Into<Rect>) {
+        let region = rect.into().intersection(&self.clip.get());
+        let mut guard = self.lock();
+        let raster_op = guard.vga.graphics_controller_registers.get_raster_op();
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(RasterOp::Replace);
+        guard.fill_region(region.columns(), region.rows(), Color16::Black);
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+    }
+
+    /// Inverts the color of every pixel in `rect` by XORing it with `0xF`.
+    /// Any part of `rect` that falls outside of the screen or the clip
+    /// rectangle is clipped.
+    ///
+    /// Inverting the same region twice restores the original pixels, which
+    /// makes for a flicker free highlight.
+    pub fn invert_region(&self, rect: impl Into<Rect>) {
+        self.invert_region_with_mask(rect, 0xF);
+    }
+
+    /// Inverts the pixels in `rect` like `invert_region`, XORing each pixel
+    /// with the low 4 bits of `mask`. A `mask` of `0x7` keeps the intensity
+    /// bit, for a subtler inversion.
+    ///
+    /// The mask is loaded into the set/reset register with `RasterOp::Xor`,
+    /// so each byte takes a single latch load and write for all four planes.
+    pub fn invert_region_with_mask(&self, rect: impl Into<Rect>, mask: u8) {
+        let region = rect.into().intersection(&self.clip.get());
+        let mut guard = self.lock();
+        let raster_op = guard.vga.graphics_controller_registers.get_raster_op();
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(RasterOp::Xor);
+        guard.fill_region(
+            region.columns(),
+            region.rows(),
+            Color16::ALL[usize::from(mask & 0xF)],
+        );
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+    }
+
+    /// Sets the given pixel at `(x, y)` using the given `raster_op`, without
+    /// changing the `RasterOp` used by other drawing operations.
+    pub fn set_pixel_op(&self, x: usize, y: usize, color: Color16, raster_op: RasterOp) {
+        let mut guard = self.lock();
+        let previous = guard.vga.graphics_controller_registers.get_raster_op();
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+        guard.set_pixel(x, y, color);
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(previous);
+    }
+
+    /// Sets the given pixel at `(x, y)` to the given `color`, returning
+    /// `OutOfBounds` instead of writing if `(x, y)` is outside of the screen.
+    pub fn try_set_pixel(&self, x: usize, y: usize, color: Color16) -> Result<(), OutOfBounds> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Err(OutOfBounds { x, y });
+        }
+
+        self.set_pixel(x, y, color);
+        Ok(())
+    }
+
+    /// Sets each of the `points` to its color while holding the lock once.
+    /// Points that fall outside of the screen or the clip rectangle are
+    /// silently discarded.
+    ///
+    /// Write mode 2 is set up once for all of the points, after which each point
+    /// only takes a read-modify-write of one byte. The bit mask register is only
+    /// reprogrammed when a point's position within its byte differs from the
+    /// previous point's, so points sorted by `x % 8` draw the fastest.
+    pub fn set_pixels(&self, points: &[(usize, usize, Color16)]) {
+        self.draw_pixels(
+            points
+                .iter()
+                .map(|&(x, y, color)| ((x as isize, y as isize), color)),
+        );
+    }
+
+    /// Returns the `Color16` of the pixel at `(x, y)`, or `None` if
+    /// `(x, y)` is outside of the screen.
+    ///
+    /// The color is reassembled by reading the pixel's bit from each of
+    /// the four planes in turn.
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<Color16> {
+        self.lock().get_pixel(x, y)
+    }
+
+    /// Copies every pixel on the screen into `out`, packed as 4 bits per pixel
+    /// with the even pixel of each pair in the high nibble, the same layout
+    /// used by `DoubleBuffered640x480x16` and `blit_bitmap`.
+    ///
+    /// The frame buffer is read one plane at a time, so each byte read
+    /// provides a bit of 8 pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` has fewer than `Graphics640x480x16::DUMP_SIZE` bytes.
+    pub fn dump_to(&self, out: &mut [u8]) {
+        self.lock().dump_to(out);
+    }
+
+    /// Saves the pixels of the rectangle starting at `top_left` with the given
+    /// `width` and `height` into `buffer`, so they can be put back with
+    /// `restore_region` once whatever is drawn over them goes away. Any part of
+    /// the rectangle that falls outside of the screen is clipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` has fewer than `RegionBuffer::required_size(width, height)`
+    /// bytes.
+    pub fn save_region<'a>(
+        &self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        buffer: &'a mut [u8],
+    ) -> RegionBuffer<'a> {
+        assert!(
+            buffer.len() >= RegionBuffer::required_size(width, height),
+            "buffer is too small"
+        );
+        let region = Rect::from_point(top_left, width, height).intersection(&Self::SCREEN);
+        let (x, y) = (region.columns().start, region.rows().start);
+        let (width, height) = (region.width, region.height);
+        let stride = width.div_ceil(2);
+        buffer[..stride * height].fill(0);
+
+        let planes = [
+            ReadPlane::Plane0,
+            ReadPlane::Plane1,
+            ReadPlane::Plane2,
+            ReadPlane::Plane3,
+        ];
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        for (plane, read_plane) in planes.iter().enumerate() {
+            vga.graphics_controller_registers
+                .write_read_plane(*read_plane);
+            for row in 0..height {
+                let data = &mut buffer[row * stride..][..stride];
+                for column in x / 8..(x + width).div_ceil(8) {
+                    let offset = column + (y + row) * Self::WIDTH_IN_BYTES;
+                    let byte = unsafe { frame_buffer.add(offset).read_volatile() };
+                    Self::unpack_bitmap_plane_byte(
+                        data,
+                        x,
+                        x..x + width,
+                        column,
+                        plane as u8,
+                        byte,
+                    );
+                }
+            }
+        }
+
+        RegionBuffer {
+            buffer,
+            top_left: region.top_left(),
+            width,
+            height,
+        }
+    }
+
+    /// Puts the pixels saved by `save_region` back where they were taken from,
+    /// ignoring the clip rectangle and the current raster op.
+    pub fn restore_region(&self, region: &RegionBuffer) {
+        let mut guard = self.lock();
+        let raster_op = guard.vga.graphics_controller_registers.get_raster_op();
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(RasterOp::Replace);
+        Self::blit_unclipped(
+            &mut guard.vga,
+            guard.frame_buffer,
+            region.top_left,
+            region.width,
+            region.height,
+            region.buffer,
+        );
+        guard
+            .vga
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+    }
+
+    /// Draws the `src` rectangle of `surface` with its top left corner at `dst`.
+    /// `src` is clipped to the surface, and any part of it that falls outside
+    /// of the screen or the clip rectangle is clipped.
+    ///
+    /// The pixels are written one plane at a time, 8 pixels per byte, like
+    /// `blit_bitmap`. A scene rendered into an `OffscreenSurface` can be shown
+    /// without flicker by calling `Vga::wait_for_vsync` before blitting it.
+    pub fn blit_surface(&self, surface: &OffscreenSurface, src: Rect, dst: impl Into<Point>) {
+        self.blit_surface_region(surface, src, dst.into(), None);
+    }
+
+    /// Draws the `src` rectangle of `surface` at `dst` like `blit_surface`,
+    /// except that pixels equal to `transparent` are skipped, leaving the
+    /// screen behind them untouched.
+    pub fn blit_surface_keyed(
+        &self,
+        surface: &OffscreenSurface,
+        src: Rect,
+        dst: impl Into<Point>,
+        transparent: Color16,
+    ) {
+        self.blit_surface_region(surface, src, dst.into(), Some(transparent));
+    }
+
+    /// Copies the rectangle starting at `src` with the given `width` and `height`
+    /// to `dst`. The source and destination may overlap. Any part of the rectangle
+    /// that falls outside of the screen at either position is clipped.
+    ///
+    /// When `src` and `dst` share the same alignment within a byte, the bytes
+    /// fully covered by each row are copied through the read latches, which moves
+    /// all four planes with a single read and write.
+    pub fn copy_rect(
+        &self,
+        src: impl Into<Point>,
+        dst: impl Into<Point>,
+        width: usize,
+        height: usize,
+    ) {
+        // Move both corners past any part that lies left of or above the screen
+        // at either position, the rest is clipped by the width and height.
+        let (src, dst) = (src.into(), dst.into());
+        let skip_x = src.x.min(dst.x).min(0).unsigned_abs();
+        let skip_y = src.y.min(dst.y).min(0).unsigned_abs();
+        let src = (
+            src.x.saturating_add_unsigned(skip_x) as usize,
+            src.y.saturating_add_unsigned(skip_y) as usize,
+        );
+        let dst = (
+            dst.x.saturating_add_unsigned(skip_x) as usize,
+            dst.y.saturating_add_unsigned(skip_y) as usize,
+        );
+        let width = width
+            .saturating_sub(skip_x)
+            .min(WIDTH.saturating_sub(src.0))
+            .min(WIDTH.saturating_sub(dst.0));
+        let height = height
+            .saturating_sub(skip_y)
+            .min(HEIGHT.saturating_sub(src.1))
+            .min(HEIGHT.saturating_sub(dst.1));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        let raster_op = vga.graphics_controller_registers.get_raster_op();
+        vga.graphics_controller_registers
+            .set_raster_op(RasterOp::Replace);
+        vga.sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+
+        for i in 0..height {
+            // Copy rows moving away from the destination so overlapping
+            // source rows are read before they are overwritten.
+            let row = if dst.1 > src.1 { height - 1 - i } else { i };
+            let src_row = (src.0, src.1 + row);
+            let dst_row = (dst.0, dst.1 + row);
+            if src.0 & 0x07 == dst.0 & 0x07 {
+                Self::copy_row_aligned(&mut vga, frame_buffer, src_row, dst_row, width);
+            } else {
+                Self::copy_row_unaligned(&mut vga, frame_buffer, src_row, dst_row, width);
+            }
+        }
+
+        vga.graphics_controller_registers.set_raster_op(raster_op);
+    }
+
+    /// Scrolls the screen up by `lines` scan lines, filling the newly exposed
+    /// rows at the bottom of the screen with `fill`.
+    pub fn scroll_up(&self, lines: usize, fill: Color16) {
+        let lines = lines.min(HEIGHT);
+        self.copy_rect((0, lines), (0, 0), WIDTH, HEIGHT - lines);
+        self.fill_region(0..WIDTH, HEIGHT - lines..HEIGHT, fill);
+    }
+
+    /// Scrolls the screen down by `lines` scan lines, filling the newly exposed
+    /// rows at the top of the screen with `fill`.
+    pub fn scroll_down(&self, lines: usize, fill: Color16) {
+        let lines = lines.min(HEIGHT);
+        self.copy_rect((0, 0), (0, lines), WIDTH, HEIGHT - lines);
+        self.fill_region(0..WIDTH, 0..lines, fill);
+    }
+
+    /// Draws the outline of a circle centered on `center` with the given
+    /// `radius` and `color`. Any part of the circle that falls outside of
+    /// the screen is clipped.
+    pub fn draw_circle(&self, center: impl Into<Point>, radius: usize, color: Color16) {
+        self.draw_arc(center, radius, 0, 7, color);
+    }
+
+    /// Draws the part of the outline of a circle centered on `center` with the
+    /// given `radius` and `color` that lies from `start_octant` to `end_octant`
+    /// inclusive. Any part of the arc that falls outside of the screen is clipped.
+    ///
+    /// Octants are numbered `0..8` counterclockwise as seen on the screen,
+    /// with octant 0 spanning from the positive x axis up to 45 degrees above
+    /// it. When `start_octant` is greater than `end_octant` the arc wraps
+    /// around through octant 0, so `(6, 1)` draws the right half of the circle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either octant is greater than 7.
+    pub fn draw_arc(
+        &self,
+        center: impl Into<Point>,
+        radius: usize,
+        start_octant: u8,
+        end_octant: u8,
+        color: Color16,
+    ) {
+        assert!(
+            start_octant < 8 && end_octant < 8,
+            "octants must be in the range 0..8"
+        );
+
+        let Point { x: cx, y: cy } = center.into();
+        self.set_write_mode_0(color);
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+
+        for point in Circle::new(radius as isize) {
+            let points = Self::octant_points(point);
+            for (octant, &(x, y)) in points.iter().enumerate() {
+                if !Self::arc_contains(octant as u8, start_octant, end_octant) {
+                    continue;
+                }
+                // Points on the axes and diagonals are shared between octants,
+                // only draw them once.
+                let drawn = points[..octant].iter().enumerate().any(|(i, &other)| {
+                    other == (x, y) && Self::arc_contains(i as u8, start_octant, end_octant)
+                });
+                if !drawn {
+                    self.write_clipped_pixel(&mut vga, frame_buffer, cx + x, cy + y, color);
+                }
+            }
+        }
+    }
+
+    /// Fills a circle centered on `center` with the given `radius` and
+    /// `color`. Any part of the circle that falls outside of the screen
+    /// is clipped.
+    pub fn fill_circle(&self, center: impl Into<Point>, radius: usize, color: Color16) {
+        let Point { x: cx, y: cy } = center.into();
+        let mut points = Circle::new(radius as isize).peekable();
+
+        while let Some((x, y)) = points.next() {
+            // Every `y` is visited exactly once, giving the rows closest to the center.
+            self.fill_span(cx - x, cx + x, cy + y, color);
+            if y != 0 {
+                self.fill_span(cx - x, cx + x, cy - y, color);
+            }
+
+            // Rows further from the center are filled once `x` is about to
+            // change, when `y` holds the widest span for that row.
+            let x_changes = points.peek().is_none_or(|&(next_x, _)| next_x != x);
+            if x_changes && x > y {
+                self.fill_span(cx - y, cx + y, cy + x, color);
+                self.fill_span(cx - y, cx + y, cy - x, color);
+            }
+        }
+    }
+
+    /// Draws the outline of an axis-aligned ellipse centered on `center` with
+    /// the horizontal radius `rx`, vertical radius `ry` and `color`. Any part
+    /// of the ellipse that falls outside of the screen is clipped, and a radius
+    /// of 0 draws a line along the other axis.
+    pub fn draw_ellipse(&self, center: impl Into<Point>, rx: usize, ry: usize, color: Color16) {
+        let Point { x: cx, y: cy } = center.into();
+        self.set_write_mode_0(color);
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+
+        for (x, y) in Ellipse::new(rx as isize, ry as isize) {
+            let points = [
+                (cx + x, cy + y),
+                (cx + x, cy - y),
+                (cx - x, cy + y),
+                (cx - x, cy - y),
+            ];
+            // Points on the axes are shared between quadrants, only draw them once.
+            for (i, &(px, py)) in points.iter().enumerate() {
+                if !points[..i].contains(&(px, py)) {
+                    self.write_clipped_pixel(&mut vga, frame_buffer, px, py, color);
+                }
+            }
+        }
+    }
+
+    /// Fills an axis-aligned ellipse centered on `center` with the horizontal
+    /// radius `rx`, vertical radius `ry` and `color`. Any part of the ellipse
+    /// that falls outside of the screen is clipped.
+    pub fn fill_ellipse(&self, center: impl Into<Point>, rx: usize, ry: usize, color: Color16) {
+        let Point { x: cx, y: cy } = center.into();
+        let mut points = Ellipse::new(rx as isize, ry as isize).peekable();
+
+        while let Some((x, y)) = points.next() {
+            // Points are visited with `x` increasing, so the last point
+            // of each row holds its widest span.
+            if points.peek().is_none_or(|&(_, next_y)| next_y != y) {
+                self.fill_span(cx - x, cx + x, cy + y, color);
+                if y != 0 {
+                    self.fill_span(cx - x, cx + x, cy - y, color);
+                }
+            }
+        }
+    }
+
+    /// Replaces the color of the pixel at `(x, y)` and every pixel of the same
+    /// color connected to it horizontally or vertically with `color`, stopping
+    /// at the edges of the clip rectangle. The fill is always drawn with
+    /// `RasterOp::Replace`.
+    ///
+    /// The region is filled one horizontal span at a time, keeping the spans still
+    /// to be visited on a fixed size stack of `FLOOD_FILL_CAPACITY` entries rather
+    /// than recursing. Returns `false` if a region was too complex for the stack,
+    /// in which case parts of it may be left unfilled.
+    pub fn flood_fill(&self, x: usize, y: usize, color: Color16) -> bool {
+        if !self.clip.get().contains((x as isize, y as isize)) {
+            return true;
+        }
+
+        let frame_buffer = self.get_frame_buffer();
+        let target = Self::read_pixel(&mut VGA.lock(), frame_buffer, x, y);
+        if target == color {
+            return true;
+        }
+
+        let raster_op = self.get_raster_op();
+        self.set_raster_op(RasterOp::Replace);
+        let clip = self.clip.get();
+        let (columns, rows) = (clip.columns(), clip.rows());
+        let mut stack = [(0u16, 0u16); FLOOD_FILL_CAPACITY];
+        stack[0] = (x as u16, y as u16);
+        let mut len = 1;
+        let mut complete = true;
+
+        while len > 0 {
+            len -= 1;
+            let (x, y) = (usize::from(stack[len].0), usize::from(stack[len].1));
+            let mut vga = VGA.lock();
+            if Self::read_pixel(&mut vga, frame_buffer, x, y) != target {
+                continue;
+            }
+
+            let (mut left, mut right) = (x, x);
+            while left > columns.start
+                && Self::read_pixel(&mut vga, frame_buffer, left - 1, y) == target
+            {
+                left -= 1;
+            }
+            while right + 1 < columns.end
+                && Self::read_pixel(&mut vga, frame_buffer, right + 1, y) == target
+            {
+                right += 1;
+            }
+            drop(vga);
+            self.fill_region(left..right + 1, y..y + 1, color);
+
+            // Push the start of every run of the target color
+            // directly above and below the filled span.
+            let mut vga = VGA.lock();
+            for row in y.checked_sub(1).into_iter().chain(Some(y + 1)) {
+                if !rows.contains(&row) {
+                    continue;
+                }
+                let mut in_run = false;
+                for column in left..=right {
+                    let matches = Self::read_pixel(&mut vga, frame_buffer, column, row) == target;
+                    if matches && !in_run {
+                        if len < stack.len() {
+                            stack[len] = (column as u16, row as u16);
+                            len += 1;
+                        } else {
+                            complete = false;
+                        }
+                    }
+                    in_run = matches;
+                }
+            }
+        }
+
+        self.set_raster_op(raster_op);
+        complete
+    }
+
+    /// Fills the triangle with the vertices `v0`, `v1` and `v2` with the given
+    /// `color`, one horizontal span per row. Any part of the triangle that falls
+    /// outside of the screen is clipped, and triangles with no area are drawn
+    /// as a line.
+    pub fn fill_triangle(
+        &self,
+        v0: impl Into<Point>,
+        v1: impl Into<Point>,
+        v2: impl Into<Point>,
+        color: Color16,
+    ) {
+        let mut vertices: [Point; 3] = [v0.into(), v1.into(), v2.into()];
+        vertices.sort_unstable_by_key(|vertex| vertex.y);
+        let [top, middle, bottom] = vertices;
+
+        for y in top.y.max(0)..=bottom.y.min(HEIGHT as isize - 1) {
+            let long = Self::edge_x(top, bottom, y);
+            let short = if y < middle.y {
+                Self::edge_x(top, middle, y)
+            } else {
+                Self::edge_x(middle, bottom, y)
+            };
+            let (mut x_start, mut x_end) = (long.min(short), long.max(short));
+            // Make sure vertices on a flat edge are included.
+            for vertex in vertices.iter().filter(|vertex| vertex.y == y) {
+                x_start = x_start.min(vertex.x);
+                x_end = x_end.max(vertex.x);
+            }
+            self.fill_span(x_start, x_end, y, color);
+        }
+    }
+
+    /// Draws the outline of the rectangle starting at `top_left` with the
+    /// given `width`, `height` and `color`. Each pixel of the outline is
+    /// only drawn once, including the corners.
+    pub fn draw_rect(
+        &self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        color: Color16,
+    ) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let Point { x, y } = top_left.into();
+        let right = x.saturating_add_unsigned(width - 1);
+        let bottom = y.saturating_add_unsigned(height - 1);
+
+        self.fill_rect((x, y), width, 1, color);
+        if height > 1 {
+            self.fill_rect((x, bottom), width, 1, color);
+        }
+        if height > 2 {
+            self.fill_rect((x, y + 1), 1, height - 2, color);
+            if width > 1 {
+                self.fill_rect((right, y + 1), 1, height - 2, color);
+            }
+        }
+    }
+
+    /// Draws the outline of the rectangle starting at `top_left` with the
+    /// given `width`, `height` and `color`, rounding each corner with a quarter
+    /// circle of the given `radius`. The radius is clamped so the corners never
+    /// overlap, and a radius of 0 draws the same outline as `draw_rect`.
+    ///
+    /// Each pixel of the outline is only drawn once, including where the
+    /// corners meet the edges.
+    pub fn draw_rounded_rect(
+        &self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        radius: usize,
+        color: Color16,
+    ) {
+        let top_left = top_left.into();
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let radius = radius.min((width.min(height) - 1) / 2);
+        if radius == 0 {
+            self.draw_rect(top_left, width, height, color);
+            return;
+        }
+
+        // The centers of the corner arcs.
+        let Point { x, y } = top_left;
+        let (left, top) = (x + radius as isize, y + radius as isize);
+        let right = x.saturating_add_unsigned(width - 1 - radius);
+        let bottom = y.saturating_add_unsigned(height - 1 - radius);
+
+        // Corners that share a center are drawn as a single arc, so the
+        // points where they meet aren't drawn twice.
+        match (left == right, top == bottom) {
+            (true, true) => self.draw_circle((left, top), radius, color),
+            (true, false) => {
+                self.draw_arc((left, top), radius, 0, 3, color);
+                self.draw_arc((left, bottom), radius, 4, 7, color);
+            }
+            (false, true) => {
+                self.draw_arc((left, top), radius, 2, 5, color);
+                self.draw_arc((right, top), radius, 6, 1, color);
+            }
+            (false, false) => {
+                self.draw_arc((right, top), radius, 0, 1, color);
+                self.draw_arc((left, top), radius, 2, 3, color);
+                self.draw_arc((left, bottom), radius, 4, 5, color);
+                self.draw_arc((right, bottom), radius, 6, 7, color);
+            }
+        }
+
+        if right - left > 1 {
+            let length = (right - left - 1) as usize;
+            self.fill_rect((left + 1, y), length, 1, color);
+            self.fill_rect((left + 1, bottom + radius as isize), length, 1, color);
+        }
+        if bottom - top > 1 {
+            let length = (bottom - top - 1) as usize;
+            self.fill_rect((x, top + 1), 1, length, color);
+            self.fill_rect((right + radius as isize, top + 1), 1, length, color);
+        }
+    }
+
+    /// Fills the screen with 16 vertical bars showing every `Color16` in
+    /// order from left to right, ignoring the clip rectangle and the current
+    /// raster op. This is useful to confirm the mode and palette were set up
+    /// correctly.
+    pub fn draw_test_pattern(&self) {
+        let raster_op = self.get_raster_op();
+        self.set_raster_op(RasterOp::Replace);
+        let bar_width = WIDTH / Color16::ALL.len();
+        for (i, color) in Color16::all().enumerate() {
+            self.fill_region(i * bar_width..(i + 1) * bar_width, 0..HEIGHT, color);
+        }
+        self.set_raster_op(raster_op);
+    }
+
+    /// Draws a horizontal line on row `y` from `x0` to `x1` inclusive with the
+    /// given `color`. Any part of the line that falls outside of the screen is
+    /// clipped.
+    ///
+    /// Unlike `draw_line`, the bytes fully covered by the line are written 8 pixels
+    /// at a time through the set/reset register.
+    pub fn draw_horizontal_line(&self, x0: usize, x1: usize, y: usize, color: Color16) {
+        // Clamp before measuring, so spans reaching `usize::MAX` can't overflow.
+        let (x_start, x_end) = (x0.min(x1), x0.max(x1).min(WIDTH - 1));
+        if x_start > x_end || y >= HEIGHT {
+            return;
+        }
+        self.fill_rect((x_start, y), x_end - x_start + 1, 1, color);
+    }
+
+    /// Draws a vertical line in column `x` from `y0` to `y1` inclusive with the
+    /// given `color`. Any part of the line that falls outside of the screen is
+    /// clipped.
+    ///
+    /// The bit mask is only set once, each row then takes a single latch load and
+    /// write, stepping through the frame buffer by a row at a time.
+    pub fn draw_vertical_line(&self, x: usize, y0: usize, y1: usize, color: Color16) {
+        // Clamp before measuring, so spans reaching `usize::MAX` can't overflow.
+        let (y_start, y_end) = (y0.min(y1), y0.max(y1).min(HEIGHT - 1));
+        if y_start > y_end || x >= WIDTH {
+            return;
+        }
+        self.fill_rect((x, y_start), 1, y_end - y_start + 1, color);
+    }
+
+    /// Draws a line from `start` to `end` with the given `color`, skipping
+    /// the pixels that are off in `style`. The line is clipped to the clip
+    /// rectangle.
+    pub fn draw_line_styled(
+        &self,
+        start: impl Into<Point>,
+        end: impl Into<Point>,
+        color: Color16,
+        style: LineStyle,
+    ) {
+        self.lock()
+            .draw_line_styled_with_phase(start, end, color, style, 0);
+    }
+
+    /// Draws a line from `start` to `end` like `draw_line_styled`, with the
+    /// pattern of `style` starting at `phase` rather than 0.
+    ///
+    /// Returns the phase of `end`, so passing it to the next segment of a
+    /// polyline, which starts at `end`, keeps the pattern continuous.
+    pub fn draw_line_styled_with_phase(
+        &self,
+        start: impl Into<Point>,
+        end: impl Into<Point>,
+        color: Color16,
+        style: LineStyle,
+        phase: usize,
+    ) -> usize {
+        self.lock()
+            .draw_line_styled_with_phase(start, end, color, style, phase)
+    }
+
+    /// Draws a quadratic Bézier curve from `p0` to `p2`, bent towards the
+    /// control point `p1`, with the given `color`. Any part of the curve that
+    /// falls outside of the clip rectangle is clipped.
+    ///
+    /// The curve is evaluated with integer math only, in as many steps as the
+    /// larger side of the bounding box of its control points up to 1024, and
+    /// the steps are joined with lines.
+    pub fn draw_quadratic_bezier(
+        &self,
+        p0: impl Into<Point>,
+        p1: impl Into<Point>,
+        p2: impl Into<Point>,
+        color: Color16,
+    ) {
+        let (p0, p1, p2): (Point, Point, Point) = (p0.into(), p1.into(), p2.into());
+        let curve = Bezier::quadratic(p0.into(), p1.into(), p2.into());
+        self.lock().draw_curve(curve, color);
+    }
+
+    /// Draws a cubic Bézier curve from `p0` to `p3` with the control points
+    /// `p1` and `p2` and the given `color`, like `draw_quadratic_bezier`.
+    pub fn draw_cubic_bezier(
+        &self,
+        p0: impl Into<Point>,
+        p1: impl Into<Point>,
+        p2: impl Into<Point>,
+        p3: impl Into<Point>,
+        color: Color16,
+    ) {
+        let (p0, p1, p2, p3): (Point, Point, Point, Point) =
+            (p0.into(), p1.into(), p2.into(), p3.into());
+        let curve = Bezier::cubic(p0.into(), p1.into(), p2.into(), p3.into());
+        self.lock().draw_curve(curve, color);
+    }
+
+    /// Draws a line from `start` to `end` that is `thickness` pixels wide with
+    /// the given `color` and `LineCap::Square` caps, so a thickness of 1 covers
+    /// the same pixels as `draw_line`. Any part of the line that falls outside
+    /// of the screen is clipped.
+    pub fn draw_thick_line(
+        &self,
+        start: impl Into<Point>,
+        end: impl Into<Point>,
+        thickness: usize,
+        color: Color16,
+    ) {
+        self.draw_thick_line_with_cap(start, end, thickness, LineCap::Square, color);
+    }
+
+    /// Draws a line from `start` to `end` that is `thickness` pixels wide with
+    /// the given `cap` and `color`. Any part of the line that falls outside of
+    /// the screen is clipped.
+    ///
+    /// The outline of the line, including its caps, is filled one horizontal span
+    /// per row, so diagonal lines have no gaps and no pixel is drawn twice.
+    pub fn draw_thick_line_with_cap(
+        &self,
+        start: impl Into<Point>,
+        end: impl Into<Point>,
+        thickness: usize,
+        cap: LineCap,
+        color: Color16,
+    ) {
+        let (start, end): (Point, Point) = (start.into(), end.into());
+        let line = ThickLine::new(start.into(), end.into(), thickness, cap);
+        let rows = line.rows();
+        for y in rows.start.max(0)..rows.end.min(HEIGHT as isize) {
+            if let Some((x_start, x_end)) = line.span(y) {
+                self.fill_span(x_start, x_end, y, color);
+            }
+        }
+    }
+
+    /// Draws connected line segments through each of the `points` in turn with
+    /// the given `color`. A single point draws a single pixel, and any part of
+    /// the lines that falls outside of the screen is clipped.
+    ///
+    /// The points where segments meet are only drawn once.
+    pub fn draw_polyline(&self, points: &[impl Into<Point> + Copy], color: Color16) {
+        self.draw_segments(points, false, color);
+    }
+
+    /// Draws the outline of the polygon with the given `points` as vertices
+    /// with the given `color`, closing it back to the first point. Any part of
+    /// the outline that falls outside of the screen is clipped.
+    ///
+    /// Each vertex is only drawn once.
+    pub fn draw_polygon(&self, points: &[impl Into<Point> + Copy], color: Color16) {
+        self.draw_segments(points, true, color);
+    }
+
+    /// Fills the polygon with the given `points` as vertices with the given `color`,
+    /// using the even-odd rule, so concave and self-intersecting polygons are
+    /// supported. Any part of the polygon that falls outside of the screen is clipped.
+    ///
+    /// Each row is sampled through the centers of its pixels, which keeps polygons
+    /// that share an edge from overlapping. As a result, the bottom and right edges
+    /// of the polygon aren't filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `MAX_POLYGON_VERTICES` points.
+    pub fn fill_polygon(&self, points: &[impl Into<Point> + Copy], color: Color16) {
+        assert!(
+            points.len() <= Self::MAX_POLYGON_VERTICES,
+            "too many polygon vertices"
+        );
+        let mut vertices = [Point::default(); MAX_POLYGON_VERTICES];
+        for (vertex, &point) in vertices.iter_mut().zip(points) {
+            *vertex = point.into();
+        }
+        let vertices = &vertices[..points.len()];
+        let y_start = match vertices.iter().map(|vertex| vertex.y).min() {
+            Some(y_start) => y_start,
+            None => return,
+        };
+        let y_end = vertices.iter().map(|vertex| vertex.y).max().unwrap();
+
+        let mut crossings = [0isize; MAX_POLYGON_VERTICES];
+        for y in y_start.max(0)..y_end.min(HEIGHT as isize) {
+            let crossings = Self::polygon_crossings(vertices, y, &mut crossings);
+            crossings.sort_unstable();
+            for pair in crossings.chunks_exact(2) {
+                if pair[1] > pair[0] {
+                    self.fill_span(pair[0], pair[1] - 1, y, color);
+                }
+            }
+        }
+    }
+
+    /// Fills the rectangle starting at `top_left` with the given `width`,
+    /// `height` and `color`. Any part of the rectangle that falls outside
+    /// of the screen or the clip rectangle is clipped.
+    ///
+    /// Bytes that are fully covered by the rectangle are written with a single
+    /// write through the set/reset register, only the left and right edges
+    /// need to go through the read latches.
+    pub fn fill_rect(
+        &self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        color: Color16,
+    ) {
+        self.lock().fill_rect(top_left, width, height, color);
+    }
+
+    /// Fills `rect` with a gradient from `start` to `end` along `direction`. Any
+    /// part of `rect` that falls outside of the screen or the clip rectangle is
+    /// clipped.
+    ///
+    /// Since the pixels can't blend two colors, the gradient is drawn with ordered
+    /// dithering, switching more of each 4x4 block of pixels from `start` to `end`
+    /// over 16 steps along the gradient.
+    pub fn fill_rect_gradient(
+        &self,
+        rect: Rect,
+        start: Color16,
+        end: Color16,
+        direction: GradientDirection,
+    ) {
+        let region = rect.intersection(&self.clip.get());
+        let takes_end = |x, y| gradient_dither(x, y, direction.level(&rect, x, y));
+        let mut guard = self.lock();
+        guard.fill_masked(region, start, |x, y| !takes_end(x, y));
+        guard.fill_masked(region, end, takes_end);
+    }
+
+    /// Fills the pixels in `columns` for each of the given `rows`, which must
+    /// lie within the screen, ignoring the clip rectangle.
+    fn fill_region(&self, columns: Range<usize>, rows: Range<usize>, color: Color16) {
+        self.lock().fill_region(columns, rows, color);
+    }
+
+    fn set_write_mode_0(&self, color: Color16) {
+        self.lock().set_write_mode_0(color);
+    }
+
+    fn set_write_mode_2(&self) {
+        self.lock().set_write_mode_2();
+    }
+
+    /// Writes a single pixel using an already locked `Vga`, which lets
+    /// callers drawing many pixels avoid re-locking per pixel.
+    #[inline]
+    fn write_pixel(vga: &mut Vga, frame_buffer: *mut u8, x: usize, y: usize, color: Color16) {
+        let offset = x / 8 + y * Self::WIDTH_IN_BYTES;
+        let pixel_mask = 0x80 >> (x & 0x07);
+        vga.graphics_controller_registers.set_bit_mask(pixel_mask);
+        unsafe {
+            frame_buffer.add(offset).read_volatile();
+            frame_buffer.add(offset).write_volatile(u8::from(color));
+        }
+    }
+
+    /// Sets each pixel to its color while holding the lock once, skipping
+    /// pixels that fall outside of the screen or the clip rectangle.
+    ///
+    /// The bit mask is only written when it differs from the previous pixel's,
+    /// which is the only register a pixel needs in write mode 2.
+    pub(crate) fn draw_pixels(
+        &self,
+        pixels: impl IntoIterator<Item = (Coordinates<isize>, Color16)>,
+    ) {
+        self.set_write_mode_2();
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        let mut current_mask = None;
+        for ((x, y), color) in pixels {
+            if !self.clip.get().contains((x, y)) {
+                continue;
+            }
+            let offset = x as usize / 8 + y as usize * Self::WIDTH_IN_BYTES;
+            let pixel_mask = 0x80 >> (x & 0x07);
+            if current_mask != Some(pixel_mask) {
+                vga.graphics_controller_registers.set_bit_mask(pixel_mask);
+                current_mask = Some(pixel_mask);
+            }
+            unsafe {
+                frame_buffer.add(offset).read_volatile();
+                frame_buffer.add(offset).write_volatile(u8::from(color));
+            }
+        }
+    }
+
+    /// Calls `draw` with the position of each character in `string`, advancing
+    /// `advance` pixels per character and wrapping to the next line of
+    /// `line_height` pixels at the right edge of the screen or on `\n`, and
+    /// stopping at the bottom of the screen.
+    fn layout_string(
+        x: usize,
+        y: usize,
+        string: &str,
+        advance: usize,
+        line_height: usize,
+        mut draw: impl FnMut(usize, usize, char),
+    ) {
+        let (mut column, mut row) = (x, y);
+        for character in string.chars() {
+            if character == '\n' || column + advance > WIDTH {
+                column = x;
+                row += line_height;
+            }
+            if row + line_height > HEIGHT {
+                return;
+            }
+            if character != '\n' {
+                draw(column, row, character);
+                column += advance;
+            }
+        }
+    }
+
+    /// Reads the color of a single pixel using an already locked `Vga`.
+    fn read_pixel(vga: &mut Vga, frame_buffer: *mut u8, x: usize, y: usize) -> Color16 {
+        let offset = x / 8 + y * Self::WIDTH_IN_BYTES;
+        let pixel_mask = 0x80 >> (x & 0x07);
+        let planes = [
+            ReadPlane::Plane0,
+            ReadPlane::Plane1,
+            ReadPlane::Plane2,
+            ReadPlane::Plane3,
+        ];
+        let mut color = 0;
+
+        for (bit, plane) in planes.iter().enumerate() {
+            vga.graphics_controller_registers.write_read_plane(*plane);
+            let byte = unsafe { frame_buffer.add(offset).read_volatile() };
+            if byte & pixel_mask != 0 {
+                color |= 1 << bit;
+            }
+        }
+
+        // A nibble always maps to a valid `Color16`.
+        Color16::from_nibble(color).unwrap()
+    }
+
+    /// Copies `width` pixels of a single row from `src` to `dst`, where both
+    /// share the same alignment within a byte. The ragged edges are copied per
+    /// pixel and the bytes in between through the read latches.
+    fn copy_row_aligned(
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        src: Coordinates<usize>,
+        dst: Coordinates<usize>,
+        width: usize,
+    ) {
+        let left_pixels = match src.0 & 0x07 {
+            0 => 0,
+            bits => (8 - bits).min(width),
+        };
+        let bytes = (width - left_pixels) / 8;
+        let right_pixels = (width - left_pixels) % 8;
+        let right_start = left_pixels + bytes * 8;
+
+        // Read the edges first, the latch copy may overwrite them when
+        // the source and destination share a row.
+        let mut left = [Color16::Black; 8];
+        let mut right = [Color16::Black; 8];
+        for (i, color) in left.iter_mut().enumerate().take(left_pixels) {
+            *color = Self::read_pixel(vga, frame_buffer, src.0 + i, src.1);
+        }
+        for (i, color) in right.iter_mut().enumerate().take(right_pixels) {
+            *color = Self::read_pixel(vga, frame_buffer, src.0 + right_start + i, src.1);
+        }
+
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode1);
+        let src_offset = (src.0 + left_pixels) / 8 + src.1 * Self::WIDTH_IN_BYTES;
+        let dst_offset = (dst.0 + left_pixels) / 8 + dst.1 * Self::WIDTH_IN_BYTES;
+        for i in 0..bytes {
+            let i = if dst_offset > src_offset {
+                bytes - 1 - i
+            } else {
+                i
+            };
+            unsafe {
+                frame_buffer.add(src_offset + i).read_volatile();
+                frame_buffer.add(dst_offset + i).write_volatile(0x00);
+            }
+        }
+
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode2);
+        for (i, color) in left.iter().enumerate().take(left_pixels) {
+            Self::write_pixel(vga, frame_buffer, dst.0 + i, dst.1, *color);
+        }
+        for (i, color) in right.iter().enumerate().take(right_pixels) {
+            Self::write_pixel(vga, frame_buffer, dst.0 + right_start + i, dst.1, *color);
+        }
+    }
+
+    /// Copies `width` pixels of a single row from `src` to `dst` one pixel
+    /// at a time, buffering the whole row first in case they overlap.
+    fn copy_row_unaligned(
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        src: Coordinates<usize>,
+        dst: Coordinates<usize>,
+        width: usize,
+    ) {
+        let mut row = [Color16::Black; WIDTH];
+        for (i, color) in row.iter_mut().enumerate().take(width) {
+            *color = Self::read_pixel(vga, frame_buffer, src.0 + i, src.1);
+        }
+
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode2);
+        for (i, color) in row.iter().enumerate().take(width) {
+            Self::write_pixel(vga, frame_buffer, dst.0 + i, dst.1, *color);
+        }
+    }
+
+    /// Writes a bitmap laid out as described by `blit_bitmap`, skipping the
+    /// pixels equal to `transparent`.
+    fn blit(
+        &self,
+        origin: Point,
+        width: usize,
+        height: usize,
+        data: &[u8],
+        transparent: Option<Color16>,
+    ) {
+        let stride = width.div_ceil(2);
+        assert!(data.len() >= stride * height, "not enough bitmap data");
+
+        let region = Rect::from_point(origin, width, height).intersection(&self.clip.get());
+        let mut guard = self.lock();
+        Self::blit_region(
+            &mut guard.vga,
+            guard.frame_buffer,
+            data,
+            stride,
+            origin,
+            region,
+            transparent,
+        );
+    }
+
+    /// Writes a bitmap laid out as described by `blit_bitmap` through `vga`,
+    /// clipped to the screen but not to the clip rectangle.
+    fn blit_unclipped(
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        origin: Point,
+        width: usize,
+        height: usize,
+        data: &[u8],
+    ) {
+        let stride = width.div_ceil(2);
+        assert!(data.len() >= stride * height, "not enough bitmap data");
+
+        let region = Rect::from_point(origin, width, height).intersection(&Self::SCREEN);
+        Self::blit_region(vga, frame_buffer, data, stride, origin, region, None);
+    }
+
+    /// Writes the pixels of `region` on the screen from a bitmap laid out as
+    /// described by `blit_bitmap`, with rows of `stride` bytes and its top left
+    /// corner at `origin`, skipping the pixels equal to `transparent`.
+    ///
+    /// `region` must lie within the screen and the bitmap, though `origin` may
+    /// fall outside of the screen when only part of a bitmap is drawn.
+    fn blit_region(
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        data: &[u8],
+        stride: usize,
+        origin: Point,
+        region: Rect,
+        transparent: Option<Color16>,
+    ) {
+        if region.is_empty() {
+            return;
+        }
+        let (columns, rows) = (region.columns(), region.rows());
+
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode0);
+        vga.graphics_controller_registers
+            .write_enable_set_reset(0x0);
+        let transparent = transparent.map(u8::from);
+        let replace = vga.graphics_controller_registers.get_raster_op() == RasterOp::Replace;
+
+        for plane in PlaneMask::ALL_PLANES.iter() {
+            vga.sequencer_registers
+                .set_plane_mask(PlaneMask::from_planes(&[plane]));
+            let mut current_mask = None;
+            for y in rows.clone() {
+                let row = &data[(y as isize - origin.y) as usize * stride..][..stride];
+                for column in columns.start / 8..columns.end.div_ceil(8) {
+                    let (byte, bit_mask) = Self::bitmap_plane_byte(
+                        row,
+                        origin.x,
+                        columns.clone(),
+                        column,
+                        plane,
+                        transparent,
+                    );
+                    if bit_mask == 0 {
+                        continue;
+                    }
+                    if current_mask != Some(bit_mask) {
+                        vga.graphics_controller_registers.set_bit_mask(bit_mask);
+                        current_mask = Some(bit_mask);
+                    }
+                    let offset = column + y * Self::WIDTH_IN_BYTES;
+                    unsafe {
+                        // Bits outside of the mask are written back from the latches.
+                        if bit_mask != 0xFF || !replace {
+                            frame_buffer.add(offset).read_volatile();
+                        }
+                        frame_buffer.add(offset).write_volatile(byte);
+                    }
+                }
+            }
+        }
+
+        vga.sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+    }
+
+    fn blit_surface_region(
+        &self,
+        surface: &OffscreenSurface,
+        src: Rect,
+        dst: Point,
+        transparent: Option<Color16>,
+    ) {
+        let source = src.intersection(&Surface::bounds(surface));
+        // The destination moves along with any part of `src` clipped off the top left.
+        let dst = Point::new(dst.x + (source.x - src.x), dst.y + (source.y - src.y));
+        let region =
+            Rect::from_point(dst, source.width, source.height).intersection(&self.clip.get());
+        let origin = Point::new(dst.x - source.x, dst.y - source.y);
+        let stride = Surface::width(surface).div_ceil(2);
+        let mut guard = self.lock();
+        Self::blit_region(
+            &mut guard.vga,
+            guard.frame_buffer,
+            surface.as_bytes(),
+            stride,
+            origin,
+            region,
+            transparent,
+        );
+    }
+
+    /// Draws line segments between consecutive `points` while holding the lock
+    /// once, plotting shared end points a single time. When `closed` is set, a
+    /// final segment is drawn from the last point back to the first.
+    fn draw_segments<P: Into<Point> + Copy>(&self, points: &[P], closed: bool, color: Color16) {
+        let to_coordinates = |&point: &P| -> Coordinates<isize> { point.into().into() };
+        let (first, rest) = match points.split_first() {
+            Some((first, rest)) => (to_coordinates(first), rest),
+            None => return,
+        };
+
+        self.set_write_mode_0(color);
+        let frame_buffer = self.get_frame_buffer();
+        let mut vga = VGA.lock();
+        self.write_clipped_pixel(&mut vga, frame_buffer, first.0, first.1, color);
+
+        let mut last = first;
+        for point in rest.iter().map(to_coordinates) {
+            for (x, y) in Bresenham::new(last, point).skip(1) {
+                self.write_clipped_pixel(&mut vga, frame_buffer, x, y, color);
+            }
+            last = point;
+        }
+
+        // A closing segment for two points would retrace the only line.
+        if closed && points.len() > 2 {
+            let mut segment = Bresenham::new(last, first).skip(1).peekable();
+            while let Some((x, y)) = segment.next() {
+                // The first point has already been drawn.
+                if segment.peek().is_some() {
+                    self.write_clipped_pixel(&mut vga, frame_buffer, x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Stores the x coordinates where the edges of the polygon with the given
+    /// `points` cross the center of row `y` in `crossings`, rounded to the
+    /// nearest pixel edge, and returns the part of `crossings` that was used.
+    ///
+    /// Edges include their top row but not their bottom row, so horizontal
+    /// edges are skipped and a vertex shared by two edges is only counted
+    /// once when the polygon passes through it.
+    fn polygon_crossings<'a>(
+        points: &[Point],
+        y: isize,
+        crossings: &'a mut [isize],
+    ) -> &'a mut [isize] {
+        let mut count = 0;
+        let edges = points.iter().zip(points.iter().cycle().skip(1));
+        for (&Point { x: x0, y: y0 }, &Point { x: x1, y: y1 }) in edges {
+            if y < y0.min(y1) || y >= y0.max(y1) {
+                continue;
+            }
+            // Solve for x at `y + 0.5`, with everything doubled to stay in integers.
+            let mut numerator = (2 * y + 1 - 2 * y0) * (x1 - x0);
+            let mut denominator = 2 * (y1 - y0);
+            if denominator < 0 {
+                numerator = -numerator;
+                denominator = -denominator;
+            }
+            crossings[count] = x0 + (2 * numerator + denominator).div_euclid(2 * denominator);
+            count += 1;
+        }
+        &mut crossings[..count]
+    }
+
+    /// Gathers the bits of `plane` for the 8 pixels at byte `column` from a `row`
+    /// of a bitmap starting at `origin_x`, returning them along with a bit mask
+    /// of the pixels that fall within `columns` and aren't `transparent`.
+    fn bitmap_plane_byte(
+        row: &[u8],
+        origin_x: isize,
+        columns: Range<usize>,
+        column: usize,
+        plane: u8,
+        transparent: Option<u8>,
+    ) -> (u8, u8) {
+        let mut byte = 0;
+        let mut bit_mask = 0;
+        for bit in 0..8 {
+            let x = column * 8 + bit;
+            if !columns.contains(&x) {
+                continue;
+            }
+            let bitmap_x = (x as isize - origin_x) as usize;
+            let pixels = row[bitmap_x / 2];
+            let nibble = match bitmap_x & 1 {
+                0 => pixels >> 4,
+                _ => pixels & 0x0F,
+            };
+            if transparent == Some(nibble) {
+                continue;
+            }
+            byte |= ((nibble >> plane) & 0x1) << (7 - bit);
+            bit_mask |= 0x80 >> bit;
+        }
+        (byte, bit_mask)
+    }
+
+    /// Stores the bits of `plane` for the 8 pixels at byte `column`, as read from
+    /// the frame buffer, into a `row` of a bitmap starting at `origin_x`, skipping
+    /// the pixels outside of `columns`. This is the inverse of `bitmap_plane_byte`.
+    fn unpack_bitmap_plane_byte(
+        row: &mut [u8],
+        origin_x: usize,
+        columns: Range<usize>,
+        column: usize,
+        plane: u8,
+        byte: u8,
+    ) {
+        for bit in 0..8 {
+            let x = column * 8 + bit;
+            if !columns.contains(&x) {
+                continue;
+            }
+            let bitmap_x = x - origin_x;
+            let value = ((byte >> (7 - bit)) & 0x1) << plane;
+            row[bitmap_x / 2] |= match bitmap_x & 1 {
+                0 => value << 4,
+                _ => value,
+            };
+        }
+    }
+
+    /// Spreads the bits of `plane` for 8 pixels, read from a single byte of the
+    /// frame buffer, across the 4 bytes of packed `pixels` they belong to.
+    #[inline]
+    fn unpack_plane_byte(pixels: &mut [u8], plane: u8, byte: u8) {
+        for (i, pixel_pair) in pixels.iter_mut().enumerate() {
+            let high = (byte >> (7 - i * 2)) & 0x1;
+            let low = (byte >> (6 - i * 2)) & 0x1;
+            *pixel_pair |= high << (4 + plane) | low << plane;
+        }
+    }
+
+    /// Reflects a `point` from the first octant of a circle into all 8 octants,
+    /// indexed by octant number. The y axis points down the screen, so the
+    /// octants above the x axis have a negative y.
+    #[inline]
+    fn octant_points((x, y): Coordinates<isize>) -> [Coordinates<isize>; 8] {
+        [
+            (x, -y),
+            (y, -x),
+            (-y, -x),
+            (-x, -y),
+            (-x, y),
+            (-y, x),
+            (y, x),
+            (x, y),
+        ]
+    }
+
+    /// Returns whether `octant` lies within the arc from `start_octant` to
+    /// `end_octant` inclusive, wrapping around through octant 0.
+    #[inline]
+    fn arc_contains(octant: u8, start_octant: u8, end_octant: u8) -> bool {
+        if start_octant <= end_octant {
+            (start_octant..=end_octant).contains(&octant)
+        } else {
+            octant >= start_octant || octant <= end_octant
+        }
+    }
+
+    /// Writes a single pixel using an already locked `Vga`, skipping
+    /// pixels that fall outside of the clip rectangle.
+    #[inline]
+    fn write_clipped_pixel(
+        &self,
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        x: isize,
+        y: isize,
+        color: Color16,
+    ) {
+        if !self.clip.get().contains((x, y)) {
+            return;
+        }
+        Self::write_pixel(vga, frame_buffer, x as usize, y as usize, color);
+    }
+
+    /// Fills the horizontal span from `x_start` to `x_end` inclusive on row `y`,
+    /// clipping any part of it that falls outside of the screen.
+    fn fill_span(&self, x_start: isize, x_end: isize, y: isize, color: Color16) {
+        if y < 0 || y >= HEIGHT as isize || x_end < 0 || x_start >= WIDTH as isize {
+            return;
+        }
+        let x_start = x_start.max(0) as usize;
+        let x_end = x_end.min(WIDTH as isize - 1) as usize;
+        self.fill_rect((x_start, y as usize), x_end - x_start + 1, 1, color);
+    }
+
+    /// Returns the x coordinate of the edge from `a` to `b` on row `y`, where
+    /// `a.y <= y <= b.y`, rounded towards `a.x`.
+    ///
+    /// The offset from `a.x` is computed in `u128`, which holds the product of
+    /// any two `isize` distances, so vertices far outside of the screen can't
+    /// overflow. The offset is at most the distance from `a.x` to `b.x`, so the
+    /// result always lies between them.
+    fn edge_x(a: Point, b: Point, y: isize) -> isize {
+        if a.y == b.y {
+            return a.x;
+        }
+        let dx = b.x as i128 - a.x as i128;
+        let offset = dx.unsigned_abs() * y.abs_diff(a.y) as u128 / b.y.abs_diff(a.y) as u128;
+        if dx < 0 {
+            (a.x as i128 - offset as i128) as isize
+        } else {
+            (a.x as i128 + offset as i128) as isize
+        }
+    }
+
+    /// Writes the set/reset color to every byte in `columns` (measured in bytes)
+    /// for each of the given `rows`, limited to the bits in `bit_mask`.
+    /// Expects `WriteMode::Mode0` with set/reset enabled for all planes.
+    fn fill_bytes(
+        vga: &mut Vga,
+        frame_buffer: *mut u8,
+        columns: Range<usize>,
+        rows: Range<usize>,
+        bit_mask: u8,
+    ) {
+        if columns.is_empty() {
+            return;
+        }
+        // A full mask with `RasterOp::Replace` overwrites every bit,
+        // so the latches don't need to be loaded first.
+        let load_latches = bit_mask != 0xFF
+            || vga.graphics_controller_registers.get_raster_op() != RasterOp::Replace;
+        vga.graphics_controller_registers.set_bit_mask(bit_mask);
+        for row in rows {
+            for column in columns.clone() {
+                let offset = column + row * Self::WIDTH_IN_BYTES;
+                unsafe {
+                    if load_latches {
+                        frame_buffer.add(offset).read_volatile();
+                    }
+                    frame_buffer.add(offset).write_volatile(0xFF);
+                }
+            }
+        }
+    }
+}
+
+/// A lock on the vga driver for drawing in a 16 color planar graphics mode,
+/// returned by `PlanarGraphics::lock`.
+///
+/// The lock is held until the guard is dropped, so a sequence of drawing
+/// operations only locks once and can't be interleaved with other drawing.
+/// All drawing respects the clip rectangle of the `PlanarGraphics` it
+/// was created from.
+pub struct FrameBufferGuard<'a, const WIDTH: usize, const HEIGHT: usize> {
+    mode: &'a PlanarGraphics<WIDTH, HEIGHT>,
+    vga: SpinlockGuard<'a, Vga>,
+    frame_buffer: *mut u8,
+    /// Whether the registers are known to be set up for `WriteMode::Mode2`.
+    write_mode_2: bool,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> FrameBufferGuard<'_, WIDTH, HEIGHT> {
+    /// Clears the screen by setting all pixels to the specified `color`,
+    /// always using `RasterOp::Replace`.
+    ///
+    /// The registers are programmed so that every write covers 8 pixels in
+    /// all four planes, without loading the latches first:
+    ///
+    /// - `WriteMode::Mode0` with `color` in the set/reset register and set/reset
+    ///   enabled for all planes, so the written data is ignored.
+    /// - `RasterOp::Replace` and a bit mask of `0xFF`, so no bit comes from the
+    ///   latches.
+    /// - `PlaneMask::ALL_PLANES`, so each write reaches all four planes.
+    ///
+    /// The frame buffer is then written 4 bytes at a time, which the vga splits
+    /// into byte writes on its end, for a total of `WIDTH * HEIGHT / 32` writes.
+    /// Any bytes left over when a plane isn't a multiple of 4 bytes long are
+    /// written one at a time.
+    pub fn clear_screen(&mut self, color: Color16) {
+        self.set_write_mode_0(color);
+        let raster_op = self.vga.graphics_controller_registers.get_raster_op();
+        self.vga
+            .graphics_controller_registers
+            .set_raster_op(RasterOp::Replace);
+        self.vga.graphics_controller_registers.set_bit_mask(0xFF);
+        self.vga
+            .sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+        let size = PlanarGraphics::<WIDTH, HEIGHT>::SIZE;
+        let frame_buffer = self.frame_buffer as *mut u32;
+        for offset in 0..size / 4 {
+            unsafe {
+                // The data is ignored, every bit comes from the set/reset register.
+                frame_buffer.add(offset).write_volatile(0xFFFF_FFFF);
+            }
+        }
+        for offset in size / 4 * 4..size {
+            unsafe {
+                self.frame_buffer.add(offset).write_volatile(0xFF);
+            }
+        }
+        self.vga
+            .graphics_controller_registers
+            .set_raster_op(raster_op);
+    }
+
+    /// Draws a line from `start` to `end` with the specified `color`, clipped
+    /// to the clip rectangle.
+    pub fn draw_line(&mut self, start: impl Into<Point>, end: impl Into<Point>, color: Color16) {
+        let (start, end) = match clip_line(start, end, &self.mode.clip.get()) {
+            Some(line) => line,
+            None => return,
+        };
+        self.set_write_mode_0(color);
+        for (x, y) in Bresenham::new(start, end) {
+            self.write_clipped_pixel(x, y, color);
+        }
+    }
+
+    /// Draws a line from `start` to `end` with the specified `color`, clipped
+    /// to the clip rectangle, skipping the pixels that are off in `style`.
+    /// The pattern of `style` starts at `phase`, and the phase of `end`
+    /// is returned.
+    pub fn draw_line_styled_with_phase(
+        &mut self,
+        start: impl Into<Point>,
+        end: impl Into<Point>,
+        color: Color16,
+        style: LineStyle,
+        phase: usize,
+    ) -> usize {
+        // Bresenham takes one step per pixel along the major axis, so the phase
+        // of a point is its distance from `start` along that axis.
+        let steps = |from: Point, to: Point| from.x.abs_diff(to.x).max(from.y.abs_diff(to.y));
+        let (start, end) = (start.into(), end.into());
+        let end_phase = phase.wrapping_add(steps(start, end));
+        let (clipped_start, clipped_end) = match clip_line(start, end, &self.mode.clip.get()) {
+            Some(line) => line,
+            None => return end_phase,
+        };
+        self.set_write_mode_0(color);
+        let phase = phase.wrapping_add(steps(start, clipped_start.into()));
+        for (i, (x, y)) in Bresenham::new(clipped_start, clipped_end).enumerate() {
+            if style.is_on(phase.wrapping_add(i)) {
+                self.write_clipped_pixel(x, y, color);
+            }
+        }
+        end_phase
+    }
+
+    /// Draws a character at the given `(x, y)` coordinate to the specified
+    /// `color`. Any part of the character that falls outside of the screen
+    /// or the clip rectangle is clipped.
+    pub fn draw_character(&mut self, x: usize, y: usize, character: char, color: Color16) {
+        let clip = self.mode.clip.get();
+        self.draw_character_clipped(x as isize, y as isize, character, color, &clip);
+    }
+
+    /// Draws a character like `draw_character`, leaving out any pixel
+    /// outside of `clip`, which has to lie within the screen.
+    fn draw_character_clipped(
+        &mut self,
+        x: isize,
+        y: isize,
+        character: char,
+        color: Color16,
+        clip: &Rect,
+    ) {
+        self.set_write_mode_2();
+        for (row, byte) in basic_glyph(character).iter().enumerate() {
+            for bit in 0..8 {
+                let (x, y) = (x + bit, y + row as isize);
+                if *byte & 1 << bit != 0 && clip.contains((x, y)) {
+                    PlanarGraphics::<WIDTH, HEIGHT>::write_pixel(
+                        &mut self.vga,
+                        self.frame_buffer,
+                        x as usize,
+                        y as usize,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Draws a character like `draw_character`, with every pixel of the font
+    /// drawn as a `scale` by `scale` block. Each run of set pixels in a row of
+    /// the glyph is filled at once.
+    pub fn draw_character_scaled(
+        &mut self,
+        x: usize,
+        y: usize,
+        character: char,
+        color: Color16,
+        scale: usize,
+    ) {
+        let clip = self.mode.clip.get();
+        for (row, &byte) in basic_glyph(character).iter().enumerate() {
+            let mut bit = 0;
+            while bit < 8 {
+                if byte & 1 << bit == 0 {
+                    bit += 1;
+                    continue;
+                }
+                let start = bit;
+                while bit < 8 && byte & 1 << bit != 0 {
+                    bit += 1;
+                }
+                let block = Rect::new(
+                    (x + start * scale) as isize,
+                    (y + row * scale) as isize,
+                    (bit - start) * scale,
+                    scale,
+                )
+                .intersection(&clip);
+                self.fill_region(block.columns(), block.rows(), color);
+            }
+        }
+    }
+
+    /// Sets the given pixel at `(x, y)` to the given `color`.
+    ///
+    /// Write mode 2 is only set up by the first call in a row, after which
+    /// each pixel takes a single bit mask write and a read-modify-write of
+    /// one byte, with the hardware spreading `color` across all four planes.
+    ///
+    /// `(x, y)` is not bounds checked in release builds. Pixels outside of
+    /// the clip rectangle are silently discarded.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color16) {
+        debug_assert!(x < WIDTH && y < HEIGHT, "({}, {}) is off screen", x, y);
+        if !self.mode.clip.get().contains((x as isize, y as isize)) {
+            return;
+        }
+        self.set_write_mode_2();
+        PlanarGraphics::<WIDTH, HEIGHT>::write_pixel(&mut self.vga, self.frame_buffer, x, y, color);
+    }
+
+    /// Returns the `Color16` of the pixel at `(x, y)`, or `None` if
+    /// `(x, y)` is outside of the screen.
+    pub fn get_pixel(&mut self, x: usize, y: usize) -> Option<Color16> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+
+        Some(PlanarGraphics::<WIDTH, HEIGHT>::read_pixel(
+            &mut self.vga,
+            self.frame_buffer,
+            x,
+            y,
+        ))
+    }
+
+    /// Copies every pixel on the screen into `out`, see
+    /// `Graphics640x480x16::dump_to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` has fewer than `Graphics640x480x16::DUMP_SIZE` bytes.
+    pub fn dump_to(&mut self, out: &mut [u8]) {
+        assert!(
+            out.len() >= PlanarGraphics::<WIDTH, HEIGHT>::DUMP_SIZE,
+            "out is too small"
+        );
+        let planes = [
+            ReadPlane::Plane0,
+            ReadPlane::Plane1,
+            ReadPlane::Plane2,
+            ReadPlane::Plane3,
+        ];
+        let out = &mut out[..PlanarGraphics::<WIDTH, HEIGHT>::DUMP_SIZE];
+        out.fill(0);
+
+        for (plane, read_plane) in planes.iter().enumerate() {
+            self.vga
+                .graphics_controller_registers
+                .write_read_plane(*read_plane);
+            for (offset, pixels) in out.chunks_exact_mut(4).enumerate() {
+                let byte = unsafe { self.frame_buffer.add(offset).read_volatile() };
+                PlanarGraphics::<WIDTH, HEIGHT>::unpack_plane_byte(pixels, plane as u8, byte);
+            }
+        }
+    }
+
+    /// Fills the rectangle starting at `top_left` with the given `width`,
+    /// `height` and `color`. Any part of the rectangle that falls outside
+    /// of the screen or the clip rectangle is clipped.
+    pub fn fill_rect(
+        &mut self,
+        top_left: impl Into<Point>,
+        width: usize,
+        height: usize,
+        color: Color16,
+    ) {
+        let region = Rect::from_point(top_left, width, height).intersection(&self.mode.clip.get());
+        self.fill_region(region.columns(), region.rows(), color);
+    }
+
+    /// Sets the pixels in `region`, which must lie within the screen, for
+    /// which `covers` returns `true` to `color`.
+    ///
+    /// Each byte takes a single latch load and write for all four planes, with
+    /// the bit mask limiting it to the covered pixels.
+    fn fill_masked(&mut self, region: Rect, color: Color16, covers: impl Fn(usize, usize) -> bool) {
+        self.set_write_mode_0(color);
+        let mut current_mask = None;
+        let columns = region.columns();
+        for y in region.rows() {
+            for column in columns.start / 8..columns.end.div_ceil(8) {
+                let mut bit_mask = 0;
+                for bit in 0..8 {
+                    let x = column * 8 + bit;
+                    if columns.contains(&x) && covers(x, y) {
+                        bit_mask |= 0x80 >> bit;
+                    }
+                }
+                if bit_mask == 0 {
+                    continue;
+                }
+                if current_mask != Some(bit_mask) {
+                    self.vga
+                        .graphics_controller_registers
+                        .set_bit_mask(bit_mask);
+                    current_mask = Some(bit_mask);
+                }
+                let offset = column + y * PlanarGraphics::<WIDTH, HEIGHT>::WIDTH_IN_BYTES;
+                unsafe {
+                    self.frame_buffer.add(offset).read_volatile();
+                    self.frame_buffer.add(offset).write_volatile(0xFF);
+                }
+            }
+        }
+    }
+
+    /// Fills the pixels in `columns` for each of the given `rows`, which must
+    /// lie within the screen, ignoring the clip rectangle.
+    fn fill_region(&mut self, columns: Range<usize>, rows: Range<usize>, color: Color16) {
+        if columns.is_empty() || rows.is_empty() {
+            return;
+        }
+
+        let (x, x_end) = (columns.start, columns.end);
+        let (y, y_end) = (rows.start, rows.end);
+
+        self.set_write_mode_0(color);
+        let first_byte = x / 8;
+        let last_byte = (x_end - 1) / 8;
+        let left_mask = 0xFF >> (x & 0x07);
+        let right_mask = 0xFF << (7 - ((x_end - 1) & 0x07));
+
+        if first_byte == last_byte {
+            PlanarGraphics::<WIDTH, HEIGHT>::fill_bytes(
+                &mut self.vga,
+                self.frame_buffer,
+                first_byte..first_byte + 1,
+                y..y_end,
+                left_mask & right_mask,
+            );
+            return;
+        }
+
+        PlanarGraphics::<WIDTH, HEIGHT>::fill_bytes(
+            &mut self.vga,
+            self.frame_buffer,
+            first_byte..first_byte + 1,
+            y..y_end,
+            left_mask,
+        );
+        PlanarGraphics::<WIDTH, HEIGHT>::fill_bytes(
+            &mut self.vga,
+            self.frame_buffer,
+            first_byte + 1..last_byte,
+            y..y_end,
+            0xFF,
+        );
+        PlanarGraphics::<WIDTH, HEIGHT>::fill_bytes(
+            &mut self.vga,
+            self.frame_buffer,
+            last_byte..last_byte + 1,
+            y..y_end,
+            right_mask,
+        );
+    }
+
+    fn set_write_mode_0(&mut self, color: Color16) {
+        self.write_mode_2 = false;
+        let vga = &mut self.vga;
+        vga.graphics_controller_registers.write_set_reset(color);
+        vga.graphics_controller_registers
+            .write_enable_set_reset(0xF);
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode0);
+    }
+
+    fn set_write_mode_2(&mut self) {
+        if self.write_mode_2 {
+            return;
+        }
+        let vga = &mut self.vga;
+        vga.graphics_controller_registers
+            .set_write_mode(WriteMode::Mode2);
+        vga.graphics_controller_registers.set_bit_mask(0xFF);
+        vga.sequencer_registers
+            .set_plane_mask(PlaneMask::ALL_PLANES);
+        self.write_mode_2 = true;
+    }
+
+    #[inline]
+    fn write_clipped_pixel(&mut self, x: isize, y: isize, color: Color16) {
+        self.mode
+            .write_clipped_pixel(&mut self.vga, self.frame_buffer, x, y, color);
+    }
+
+    /// Joins the consecutive `points` with lines clipped to the clip rectangle,
+    /// drawing each pixel where two lines meet only once.
+    fn draw_curve(&mut self, mut points: impl Iterator<Item = Coordinates<isize>>, color: Color16) {
+        let mut last = match points.next() {
+            Some(point) => point,
+            None => return,
+        };
+        let clip = self.mode.clip.get();
+        self.set_write_mode_0(color);
+        self.write_clipped_pixel(last.0, last.1, color);
+        for point in points {
+            if point == last {
+                continue;
+            }
+            if let Some((start, end)) = clip_line(last, point, &clip) {
+                // `last` has already been drawn, unless it was clipped off.
+                let skip = usize::from(start == last);
+                for (x, y) in Bresenham::new(start, end).skip(skip) {
+                    self.write_clipped_pixel(x, y, color);
+                }
+            }
+            last = point;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_clip() {
+        let mode = Graphics640x480x16::new();
+        assert_eq!(mode.get_clip(), Graphics640x480x16::SCREEN);
+        mode.set_clip(Some((10, 20, 30, 40)));
+        assert_eq!(mode.get_clip(), Rect::new(10, 20, 30, 40));
+        assert!(mode.clip.get().contains((10, 20)));
+        assert!(mode.clip.get().contains((39, 59)));
+        assert!(!mode.clip.get().contains((40, 20)));
+        assert!(!mode.clip.get().contains((9, 20)));
+
+        mode.set_clip(Some((600, 470, usize::MAX, 100)));
+        assert_eq!(mode.get_clip(), Rect::new(600, 470, 40, 10));
+
+        mode.set_clip(None);
+        assert_eq!(mode.get_clip(), Graphics640x480x16::SCREEN);
+        assert!(!mode.clip.get().contains((-1, 0)));
+    }
+
+    #[test]
+    fn test_planar_graphics_resolution() {
+        assert_eq!(
+            Graphics640x480x16::VIDEO_MODE,
+            Some(VideoMode::Mode640x480x16)
+        );
+        assert_eq!(Graphics640x480x16::DUMP_SIZE, 640 * 480 / 2);
+
+        let mode = PlanarGraphics::<320, 200>::new();
+        assert_eq!(
+            PlanarGraphics::<320, 200>::VIDEO_MODE,
+            Some(VideoMode::Mode320x200x16)
+        );
+        assert_eq!(PlanarGraphics::<640, 400>::VIDEO_MODE, None);
+        assert_eq!(PlanarGraphics::<320, 200>::WIDTH_IN_BYTES, 40);
+        assert_eq!(<PlanarGraphics<320, 200> as Screen>::SIZE, 8000);
+        assert_eq!(mode.get_clip(), Rect::new(0, 0, 320, 200));
+        mode.set_clip(Some((300, 190, 100, 100)));
+        assert_eq!(mode.get_clip(), Rect::new(300, 190, 20, 10));
+    }
+
+    #[test]
+    fn test_arc_contains() {
+        for octant in 0..8 {
+            assert!(Graphics640x480x16::arc_contains(octant, 0, 7));
+            assert_eq!(Graphics640x480x16::arc_contains(octant, 3, 3), octant == 3);
+        }
+        assert!(Graphics640x480x16::arc_contains(1, 6, 1));
+        assert!(Graphics640x480x16::arc_contains(7, 6, 1));
+        assert!(!Graphics640x480x16::arc_contains(2, 6, 1));
+        assert!(!Graphics640x480x16::arc_contains(5, 6, 1));
+    }
+
+    #[test]
+    fn test_octant_points() {
+        // The first octant point `(5, 2)` sits just above the positive x axis,
+        // and octant 2 starts at the top of the circle.
+        let points = Graphics640x480x16::octant_points((5, 2));
+        assert_eq!(points[0], (5, -2));
+        assert_eq!(points[2], (-2, -5));
+        assert_eq!(points[7], (5, 2));
+        for &(x, y) in points.iter() {
+            assert_eq!(x * x + y * y, 29);
+        }
+    }
+
+    #[test]
+    fn test_unpack_bitmap_plane_byte() {
+        // A 5 pixel wide row starting at x = 6, spanning two frame buffer bytes.
+        let bitmap = [0x12, 0x34, 0x50];
+        let mut row = [0u8; 3];
+        for column in 0..2 {
+            for plane in 0..4 {
+                let (byte, _) =
+                    Graphics640x480x16::bitmap_plane_byte(&bitmap, 6, 6..11, column, plane, None);
+                Graphics640x480x16::unpack_bitmap_plane_byte(
+                    &mut row,
+                    6,
+                    6..11,
+                    column,
+                    plane,
+                    byte,
+                );
+            }
+        }
+        assert_eq!(row, bitmap);
+        assert_eq!(RegionBuffer::required_size(5, 2), 6);
+    }
+
+    #[test]
+    fn test_unpack_plane_byte() {
+        let mut pixels = [0u8; 4];
+        // Plane 1 only sets the green bit of the first and last pixel.
+        Graphics640x480x16::unpack_plane_byte(&mut pixels, 1, 0b1000_0001);
+        assert_eq!(pixels, [0x20, 0x00, 0x00, 0x02]);
+
+        Graphics640x480x16::unpack_plane_byte(&mut pixels, 0, 0b0100_0001);
+        Graphics640x480x16::unpack_plane_byte(&mut pixels, 3, 0b0100_0000);
+        assert_eq!(pixels, [0x29, 0x00, 0x00, 0x03]);
+        assert_eq!(
+            Color16::from_nibble(pixels[0] & 0x0F),
+            Some(Color16::LightBlue)
+        );
+    }
+
+    #[test]
+    fn test_edge_x() {
+        let edge_x = Graphics640x480x16::edge_x;
+        assert_eq!(edge_x(Point::new(0, 0), Point::new(10, 10), 5), 5);
+        assert_eq!(edge_x(Point::new(10, 0), Point::new(0, 3), 1), 7);
+        assert_eq!(edge_x(Point::new(4, 2), Point::new(9, 2), 2), 4);
+
+        let far = 1 << 40;
+        assert_eq!(edge_x(Point::new(-far, -far), Point::new(far, far), 0), 0);
+        assert_eq!(edge_x(Point::new(far, -far), Point::new(-far, far), 1), -1);
+
+        let (top, bottom) = (
+            Point::new(isize::MIN, isize::MIN),
+            Point::new(isize::MAX, isize::MAX),
+        );
+        assert_eq!(edge_x(top, bottom, 0), 0);
+        assert_eq!(edge_x(top, bottom, isize::MAX), isize::MAX);
+    }
+
+    #[test]
+    fn test_polygon_crossings() {
+        let mut crossings = [0; Graphics640x480x16::MAX_POLYGON_VERTICES];
+        let square = [(0, 0), (10, 0), (10, 10), (0, 10)].map(Point::from);
+        let crossings_at = |points: &[Point], y: isize, crossings: &mut [isize]| {
+            let crossings = Graphics640x480x16::polygon_crossings(points, y, crossings);
+            crossings.sort_unstable();
+            crossings.len()
+        };
+        let count = crossings_at(&square, 0, &mut crossings);
+        assert_eq!(crossings[..count], [0, 10]);
+        let count = crossings_at(&square, 9, &mut crossings);
+        assert_eq!(crossings[..count], [0, 10]);
+        let count = crossings_at(&square, 10, &mut crossings);
+        assert_eq!(count, 0);
+
+        // A concave "V" shape opening upwards.
+        let notch = [(0, 0), (4, 4), (8, 0), (8, 8), (0, 8)].map(Point::from);
+        let count = crossings_at(&notch, 1, &mut crossings);
+        assert_eq!(crossings[..count], [0, 2, 7, 8]);
+        let count = crossings_at(&notch, 6, &mut crossings);
+        assert_eq!(crossings[..count], [0, 8]);
+
+        let diagonal = [(0, 0), (10, 20), (0, 20)].map(Point::from);
+        let count = crossings_at(&diagonal, 0, &mut crossings);
+        assert_eq!(crossings[..count], [0, 0]);
+        let count = crossings_at(&diagonal, 9, &mut crossings);
+        assert_eq!(crossings[..count], [0, 5]);
+    }
+
+    #[test]
+    fn test_bitmap_plane_byte() {
+        // `Color16::Green` only has plane 1 set, `Color16::White` has all planes.
+        let row = [0x2F, 0x02, 0x00];
+        let (byte, bit_mask) = Graphics640x480x16::bitmap_plane_byte(&row, 13, 13..18, 1, 1, None);
+        assert_eq!(bit_mask, 0b0000_0111);
+        assert_eq!(byte, 0b0000_0110);
+
+        let (byte, bit_mask) = Graphics640x480x16::bitmap_plane_byte(&row, 13, 13..18, 2, 0, None);
+        assert_eq!(bit_mask, 0b1100_0000);
+        assert_eq!(byte, 0b0000_0000);
+
+        let (byte, _) = Graphics640x480x16::bitmap_plane_byte(&row, 13, 13..18, 1, 0, None);
+        assert_eq!(byte, 0b0000_0010);
+
+        let (byte, bit_mask) =
+            Graphics640x480x16::bitmap_plane_byte(&row, 13, 13..18, 1, 1, Some(0x2));
+        assert_eq!(bit_mask, 0b0000_0011);
+        assert_eq!(byte, 0b0000_0010);
+    }
+}
@@ -0,0 +1,286 @@
+use super::{GraphicsWriter, PlanarGraphics};
+use crate::{
+    colors::Color16,
+    drawing::{clip_line, Bresenham, Point, Rect},
+};
+
+/// A rectangle of pixels that can be drawn on, either on screen or in memory.
+///
+/// Implementors only need to provide the size of the surface and access to
+/// single pixels, the composite drawing methods are built on top of those.
+/// Surfaces that have faster ways to draw can override them.
+///
+/// **Note:** `Graphics640x480x16` also implements `GraphicsWriter`, whose
+/// `set_pixel` and `draw_line` take `&self` and are picked by method calls
+/// when both traits are in scope. Name the trait, like
+/// `Surface::set_pixel(&mut mode, x, y, color)`, to call the `Surface` ones.
+///
+/// # Examples
+///
+/// Drawing the same scene on screen and in memory:
+///
+/// ```no_run
+/// use vga::colors::Color16;
+/// use vga::drawing::Rect;
+/// use vga::writers::{Graphics640x480x16, GraphicsWriter, OffscreenSurface, Surface};
+///
+/// fn draw_scene<S: Surface<Color = Color16>>(surface: &mut S) {
+///     surface.clear(Color16::Black);
+///     surface.fill_rect(Rect::new(10, 10, 40, 20), Color16::Blue);
+///     surface.draw_rect(Rect::new(10, 10, 40, 20), Color16::White);
+///     surface.draw_line((0, 0), (63, 47), Color16::Yellow);
+/// }
+///
+/// let mut buffer = [0; OffscreenSurface::required_size(64, 48)];
+/// let mut offscreen = OffscreenSurface::new(&mut buffer, 64, 48);
+/// draw_scene(&mut offscreen);
+///
+/// let mut mode = Graphics640x480x16::new();
+/// mode.set_mode();
+/// draw_scene(&mut mode);
+/// ```
+pub trait Surface {
+    /// The color of a single pixel.
+    type Color: Copy;
+
+    /// Returns the width of the surface in pixels.
+    fn width(&self) -> usize;
+
+    /// Returns the height of the surface in pixels.
+    fn height(&self) -> usize;
+
+    /// Sets the pixel at `(x, y)` to `color`. Pixels outside of the
+    /// surface are silently discarded.
+    fn set_pixel(&mut self, x: usize, y: usize, color: Self::Color);
+
+    /// Returns the color of the pixel at `(x, y)`, or `None` if `(x, y)`
+    /// is outside of the surface.
+    fn get_pixel(&self, x: usize, y: usize) -> Option<Self::Color>;
+
+    /// Returns a `Rect` covering the whole surface.
+    fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width(), self.height())
+    }
+
+    /// Draws a line from `start` to `end` with the given `color`. The line is
+    /// clipped to the surface, so `start` and `end` may lie anywhere on the plane.
+    fn draw_line(&mut self, start: impl Into<Point>, end: impl Into<Point>, color: Self::Color) {
+        if let Some((start, end)) = clip_line(start, end, &self.bounds()) {
+            for (x, y) in Bresenham::new(start, end) {
+                self.set_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+
+    /// Draws the outline of `rect` with the given `color`. Each pixel of
+    /// the outline is only drawn once, including the corners.
+    fn draw_rect(&mut self, rect: Rect, color: Self::Color) {
+        if rect.is_empty() {
+            return;
+        }
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+        self.fill_rect(Rect::new(rect.x, rect.y, rect.width, 1), color);
+        if rect.height > 1 {
+            self.fill_rect(Rect::new(rect.x, bottom, rect.width, 1), color);
+        }
+        if rect.height > 2 {
+            let sides = Rect::new(rect.x, rect.y + 1, 1, rect.height - 2);
+            self.fill_rect(sides, color);
+            if rect.width > 1 {
+                self.fill_rect(Rect { x: right, ..sides }, color);
+            }
+        }
+    }
+
+    /// Fills `rect` with the given `color`. Any part of `rect` that falls
+    /// outside of the surface is clipped.
+    fn fill_rect(&mut self, rect: Rect, color: Self::Color) {
+        let rect = rect.intersection(&self.bounds());
+        for y in rect.rows() {
+            for x in rect.columns() {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Sets every pixel of the surface to `color`.
+    fn clear(&mut self, color: Self::Color) {
+        self.fill_rect(self.bounds(), color);
+    }
+}
+
+/// Draws directly to the screen, with the composite methods using the
+/// planar fast paths of `Graphics640x480x16`.
+///
+/// Drawing is limited to the clip rectangle set with `set_clip`, but
+/// `clear` always clears the whole screen.
+impl<const WIDTH: usize, const HEIGHT: usize> Surface for PlanarGraphics<WIDTH, HEIGHT> {
+    type Color = Color16;
+
+    fn width(&self) -> usize {
+        <Self as super::Screen>::WIDTH
+    }
+
+    fn height(&self) -> usize {
+        <Self as super::Screen>::HEIGHT
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: Color16) {
+        let _ = self.try_set_pixel(x, y, color);
+    }
+
+    fn get_pixel(&self, x: usize, y: usize) -> Option<Color16> {
+        PlanarGraphics::get_pixel(self, x, y)
+    }
+
+    fn draw_line(&mut self, start: impl Into<Point>, end: impl Into<Point>, color: Color16) {
+        GraphicsWriter::draw_line(self, start, end, color);
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color16) {
+        PlanarGraphics::draw_rect(self, rect.top_left(), rect.width, rect.height, color);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color16) {
+        PlanarGraphics::fill_rect(self, rect.top_left(), rect.width, rect.height, color);
+    }
+
+    fn clear(&mut self, color: Color16) {
+        self.clear_screen(color);
+    }
+}
+
+/// An in-memory `Surface` with 4 bits per pixel, stored in a borrowed buffer.
+///
+/// Each row starts on a new byte and packs two pixels per byte, with the
+/// left pixel in the high nibble. This is the same layout taken by
+/// `Graphics640x480x16::blit_bitmap`.
+#[derive(Debug)]
+pub struct OffscreenSurface<'a> {
+    buffer: &'a mut [u8],
+    width: usize,
+    height: usize,
+}
+
+impl<'a> OffscreenSurface<'a> {
+    /// Returns the number of bytes needed for a surface with the given
+    /// `width` and `height`.
+    pub const fn required_size(width: usize, height: usize) -> usize {
+        width.div_ceil(2) * height
+    }
+
+    /// Creates a new `OffscreenSurface` with the given `width` and `height`
+    /// that stores its pixels in `buffer`. The existing contents of `buffer`
+    /// are kept as the initial pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is smaller than `OffscreenSurface::required_size`.
+    pub fn new(buffer: &'a mut [u8], width: usize, height: usize) -> OffscreenSurface<'a> {
+        assert!(
+            buffer.len() >= Self::required_size(width, height),
+            "buffer is too small for the surface"
+        );
+        OffscreenSurface {
+            buffer,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the packed pixels of the surface.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..Self::required_size(self.width, self.height)]
+    }
+
+    /// Consumes the surface, returning the underlying buffer.
+    pub fn into_inner(self) -> &'a mut [u8] {
+        self.buffer
+    }
+
+    /// Returns the byte offset of the pixel at `(x, y)` and the shift
+    /// of its nibble within that byte.
+    fn pixel_offset(&self, x: usize, y: usize) -> (usize, u8) {
+        let offset = y * self.width.div_ceil(2) + x / 2;
+        let shift = if x.is_multiple_of(2) { 4 } else { 0 };
+        (offset, shift)
+    }
+}
+
+impl Surface for OffscreenSurface<'_> {
+    type Color = Color16;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: Color16) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let (offset, shift) = self.pixel_offset(x, y);
+        let byte = &mut self.buffer[offset];
+        *byte = (*byte & !(0xF << shift)) | (u8::from(color) << shift);
+    }
+
+    fn get_pixel(&self, x: usize, y: usize) -> Option<Color16> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let (offset, shift) = self.pixel_offset(x, y);
+        Some(Color16::ALL[usize::from((self.buffer[offset] >> shift) & 0xF)])
+    }
+
+    fn clear(&mut self, color: Color16) {
+        let color = u8::from(color);
+        let size = Self::required_size(self.width, self.height);
+        self.buffer[..size].fill((color << 4) | color);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_offscreen_surface_pixels() {
+        let mut buffer = [0; OffscreenSurface::required_size(5, 2)];
+        let mut surface = OffscreenSurface::new(&mut buffer, 5, 2);
+        surface.set_pixel(0, 0, Color16::Blue);
+        surface.set_pixel(1, 0, Color16::Red);
+        surface.set_pixel(4, 1, Color16::White);
+        surface.set_pixel(5, 1, Color16::Yellow);
+
+        assert_eq!(surface.get_pixel(0, 0), Some(Color16::Blue));
+        assert_eq!(surface.get_pixel(1, 0), Some(Color16::Red));
+        assert_eq!(surface.get_pixel(4, 1), Some(Color16::White));
+        assert_eq!(surface.get_pixel(5, 1), None);
+        assert_eq!(surface.as_bytes(), &[0x14, 0x00, 0x00, 0x00, 0x00, 0xF0]);
+    }
+
+    #[test]
+    fn test_offscreen_surface_shapes() {
+        let mut buffer = [0; OffscreenSurface::required_size(8, 6)];
+        let mut surface = OffscreenSurface::new(&mut buffer, 8, 6);
+        surface.clear(Color16::Blue);
+        surface.fill_rect(Rect::new(6, 4, 10, 10), Color16::Red);
+        surface.draw_rect(Rect::new(0, 0, 4, 3), Color16::White);
+        surface.draw_line((-4, 5), (3, 5), Color16::Green);
+
+        assert_eq!(
+            surface.as_bytes(),
+            &[
+                0xFF, 0xFF, 0x11, 0x11, //
+                0xF1, 0x1F, 0x11, 0x11, //
+                0xFF, 0xFF, 0x11, 0x11, //
+                0x11, 0x11, 0x11, 0x11, //
+                0x11, 0x11, 0x11, 0x44, //
+                0x22, 0x22, 0x11, 0x44, //
+            ]
+        );
+    }
+}
@@ -1,7 +1,6 @@
 use super::{Screen, TextWriter};
 use crate::{
     colors::DEFAULT_PALETTE,
-    fonts::TEXT_8X16_FONT,
     vga::{VideoMode, VGA},
 };
 
@@ -45,7 +44,6 @@ impl TextWriter for Text40x25 {
         // Some bios mess up the palette when switching modes,
         // so explicitly set it.
         vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
-        vga.load_font(&TEXT_8X16_FONT);
     }
 }
 
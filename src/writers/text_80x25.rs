@@ -1,8 +1,8 @@
-use super::{Screen, TextWriter};
+use super::{Screen, ScreenCharacter, TextWriter};
 use crate::{
-    colors::DEFAULT_PALETTE,
-    fonts::TEXT_8X16_FONT,
-    vga::{VideoMode, VGA},
+    colors::{TextModeColor, DEFAULT_PALETTE},
+    fonts::Cp437,
+    vga::{FrameBuffer, VideoMode, VGA},
 };
 
 const WIDTH: usize = 80;
@@ -44,7 +44,6 @@ impl TextWriter for Text80x25 {
         // Some bios mess up the palette when switching modes,
         // so explicitly set it.
         vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
-        vga.load_font(&TEXT_8X16_FONT);
     }
 }
 
@@ -53,4 +52,38 @@ impl Text80x25 {
     pub const fn new() -> Text80x25 {
         Text80x25
     }
+
+    /// Writes `message` in `TextModeColor::WHITE_ON_RED` starting at the top
+    /// left of the screen, writing straight to `0xB8000` without locking `VGA`
+    /// or touching any registers. Lines wrap at the right edge of the screen
+    /// and on `\n`, and anything past the last row is dropped.
+    ///
+    /// This is meant for panic handlers, where the code that panicked may still
+    /// hold the lock on `VGA` and locking it again would deadlock.
+    ///
+    /// # Safety
+    ///
+    /// The vga must already be in `VideoMode::Mode80x25`, and nothing else may
+    /// access the frame buffer while this runs. Only call this from a panic
+    /// handler with interrupts disabled, after which the screen should be
+    /// treated as owned by the panic handler.
+    pub unsafe fn emergency_write(message: &str) {
+        let frame_buffer = FrameBuffer::CgaMode.as_ptr() as *mut ScreenCharacter;
+        let mut offset = 0;
+        for character in message.chars() {
+            if offset >= SIZE {
+                return;
+            }
+            let character = match character {
+                '\n' => {
+                    offset += WIDTH - offset % WIDTH;
+                    continue;
+                }
+                character => Cp437::encode(character).unwrap_or(Cp437::REPLACEMENT),
+            };
+            let screen_character = ScreenCharacter::new(character, TextModeColor::WHITE_ON_RED);
+            frame_buffer.add(offset).write_volatile(screen_character);
+            offset += 1;
+        }
+    }
 }
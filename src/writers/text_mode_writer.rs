@@ -0,0 +1,106 @@
+use super::{ScreenCharacter, TextWriter};
+use crate::{colors::TextModeColor, fonts::Cp437};
+use core::fmt;
+
+/// A cursor based writer for vga text modes that implements `core::fmt::Write`.
+///
+/// Characters are written at the current cursor position, which advances after
+/// each character. A `\n` moves the cursor to the start of the next row and the
+/// screen is scrolled up by one row once the cursor moves past the last row.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use core::fmt::Write;
+/// use vga::colors::{Color16, TextModeColor};
+/// use vga::writers::{Text80x25, TextModeWriter, TextWriter};
+///
+/// let text_mode = Text80x25::new();
+/// let color = TextModeColor::new(Color16::Yellow, Color16::Black);
+/// let mut writer = TextModeWriter::new(text_mode, color);
+///
+/// text_mode.set_mode();
+/// text_mode.clear_screen();
+/// writeln!(writer, "Hello World!").unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TextModeWriter<T: TextWriter> {
+    text_mode: T,
+    color: TextModeColor,
+    x: usize,
+    y: usize,
+}
+
+impl<T: TextWriter> TextModeWriter<T> {
+    /// Creates a new `TextModeWriter` for the given `text_mode`, writing
+    /// characters with the specified `color` starting at `(0, 0)`.
+    pub const fn new(text_mode: T, color: TextModeColor) -> TextModeWriter<T> {
+        TextModeWriter {
+            text_mode,
+            color,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Returns the current cursor position as `(x, y)`.
+    pub fn get_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Moves the cursor to `(x, y)`, clamped to the size of the screen.
+    pub fn set_position(&mut self, x: usize, y: usize) {
+        self.x = x.min(T::WIDTH - 1);
+        self.y = y.min(T::HEIGHT - 1);
+        self.text_mode.set_cursor_position(self.x, self.y);
+    }
+
+    /// Returns the `TextModeColor` used for newly written characters.
+    pub fn get_color(&self) -> TextModeColor {
+        self.color
+    }
+
+    /// Sets the `TextModeColor` used for newly written characters.
+    pub fn set_color(&mut self, color: TextModeColor) {
+        self.color = color;
+    }
+
+    /// Writes `byte` at the current cursor position and advances the cursor.
+    pub fn write_byte(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.new_line(),
+            b'\r' => self.x = 0,
+            byte => {
+                if self.x >= T::WIDTH {
+                    self.new_line();
+                }
+                let screen_character = ScreenCharacter::new(byte, self.color);
+                self.text_mode
+                    .write_character(self.x, self.y, screen_character);
+                self.x += 1;
+            }
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.x = 0;
+        if self.y + 1 < T::HEIGHT {
+            self.y += 1;
+        } else {
+            self.text_mode.scroll_up();
+        }
+    }
+}
+
+impl<T: TextWriter> fmt::Write for TextModeWriter<T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars() {
+            self.write_byte(Cp437::encode(character).unwrap_or(Cp437::REPLACEMENT));
+        }
+        self.text_mode
+            .set_cursor_position(self.x.min(T::WIDTH - 1), self.y);
+        Ok(())
+    }
+}
@@ -4,14 +4,23 @@
 #![reexport_test_harness_main = "test_main"]
 #![test_runner(testing::test_runner)]
 
+use core::fmt::Write;
 use core::panic::PanicInfo;
 use testing::{gdt, interrupts, serial_print, serial_println};
-use vga::colors::{DEFAULT_PALETTE, PALETTE_SIZE};
+use vga::colors::{Color16, Palette, PaletteColor, TextModeColor, DEFAULT_PALETTE, PALETTE_SIZE};
 use vga::configurations::{
-    VgaConfiguration, MODE_40X25_CONFIGURATION, MODE_40X50_CONFIGURATION,
-    MODE_640X480X16_CONFIGURATION, MODE_80X25_CONFIGURATION,
+    VgaConfiguration, MODE_320X200X16_CONFIGURATION, MODE_320X200X4_CONFIGURATION,
+    MODE_40X25_CONFIGURATION, MODE_40X50_CONFIGURATION, MODE_640X350X16_CONFIGURATION,
+    MODE_640X480X16_CONFIGURATION, MODE_80X25_CONFIGURATION, MODE_80X50_CONFIGURATION,
+};
+use vga::dither::ColorMatching;
+use vga::drawing::{GradientDirection, Rect, TextAlignment};
+use vga::registers::{AttributeControllerIndex, CrtcControllerIndex, RasterOp, SequencerIndex};
+use vga::vga::{with_locked_vga, Vga, VideoMode, VGA};
+use vga::writers::{
+    Graphics320x200x256, Graphics640x480x16, GraphicsTextWriter, GraphicsWriter, OffscreenSurface,
+    PlanarGraphics, ScreenCharacter, Surface, Text80x25, TextWriter,
 };
-use vga::vga::{Vga, VideoMode, VGA};
 
 #[no_mangle] // don't mangle the name of this function
 pub extern "C" fn _start() -> ! {
@@ -44,6 +53,23 @@ fn set_mode_40x25() {
     serial_println!("[ok]");
 }
 
+#[test_case]
+fn text_mode_40x25_dot_clock() {
+    serial_print!("text mode 40x25 dot clock... ");
+
+    // Bit 3 of the clocking mode register halves the dot clock,
+    // doubling the width of each character.
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x25);
+    let clocking_mode = vga.sequencer_registers.read(SequencerIndex::ClockingMode);
+    assert_eq!(clocking_mode & 0x08, 0x00);
+    vga.set_video_mode(VideoMode::Mode40x25);
+    let clocking_mode = vga.sequencer_registers.read(SequencerIndex::ClockingMode);
+    assert_eq!(clocking_mode & 0x08, 0x08);
+
+    serial_println!("[ok]");
+}
+
 #[test_case]
 fn set_mode_40x50() {
     serial_print!("mode 40x50... ");
@@ -66,6 +92,369 @@ fn set_mode_80x25() {
     serial_println!("[ok]");
 }
 
+#[test_case]
+fn set_mode_80x50() {
+    serial_print!("mode 80x50... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x50);
+    check_registers(&mut vga, &MODE_80X50_CONFIGURATION);
+    assert!(vga.is_text_mode());
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_custom_mode() {
+    serial_print!("set custom mode... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x25);
+    vga.set_custom_mode(&MODE_640X480X16_CONFIGURATION);
+    check_registers(&mut vga, &MODE_640X480X16_CONFIGURATION);
+    assert_eq!(vga.current_video_mode(), None);
+    assert_eq!(u32::from(vga.get_frame_buffer()), 0xA0000);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn graphics_to_text_mode() {
+    serial_print!("graphics to text mode... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode640x480x16);
+    vga.set_video_mode(VideoMode::Mode80x25);
+    check_registers(&mut vga, &MODE_80X25_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn current_video_mode() {
+    serial_print!("current video mode... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x25);
+    assert_eq!(vga.current_video_mode(), Some(VideoMode::Mode80x25));
+    assert!(vga.is_text_mode() && !vga.is_graphics_mode());
+    vga.set_video_mode(VideoMode::Mode640x480x16);
+    assert_eq!(vga.current_video_mode(), Some(VideoMode::Mode640x480x16));
+    assert!(vga.is_graphics_mode() && !vga.is_text_mode());
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn with_locked_vga_640x480x16() {
+    serial_print!("with locked vga... ");
+
+    VGA.lock().set_video_mode(VideoMode::Mode640x480x16);
+    let (mode, frame_buffer) = with_locked_vga(|vga, frame_buffer| {
+        (vga.get_most_recent_video_mode(), frame_buffer as usize)
+    });
+    assert_eq!(mode, Some(VideoMode::Mode640x480x16));
+    assert_eq!(frame_buffer, 0xA0000);
+    // The lock has to be released again.
+    drop(VGA.lock());
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn save_and_restore_state() {
+    serial_print!("save and restore state... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x25);
+    let state = vga.save_state();
+    vga.set_video_mode(VideoMode::Mode640x480x16);
+    vga.restore_state(&state);
+    check_registers(&mut vga, &MODE_80X25_CONFIGURATION);
+    assert_eq!(vga.current_video_mode(), Some(VideoMode::Mode80x25));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn restore_text_mode_after_graphics() {
+    serial_print!("restore text mode after graphics... ");
+
+    let text_mode = Text80x25::new();
+    text_mode.set_mode();
+    let state = VGA.lock().save_state();
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::White);
+
+    let mut vga = VGA.lock();
+    vga.restore_state(&state);
+    check_registers(&mut vga, &MODE_80X25_CONFIGURATION);
+    drop(vga);
+
+    let blank = ScreenCharacter::new(b' ', TextModeColor::LIGHT_GREY_ON_BLACK);
+    for &(x, y) in [(0, 0), (40, 12), (79, 24)].iter() {
+        assert_eq!(text_mode.read_character(x, y), blank);
+    }
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_cursor_position() {
+    serial_print!("set cursor position... ");
+
+    let text_mode = Text80x25::new();
+    text_mode.set_mode();
+    text_mode.set_cursor_position(12, 17);
+    assert_eq!(text_mode.get_cursor_position(), (12, 17));
+    text_mode.set_cursor_position(79, 24);
+    assert_eq!(text_mode.get_cursor_position(), (79, 24));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_border_color() {
+    serial_print!("set border color... ");
+
+    let mut vga = VGA.lock();
+    let emulation_mode = vga.get_emulation_mode();
+    vga.set_border_color(Color16::Blue);
+    assert_eq!(
+        vga.attribute_controller_registers
+            .read(emulation_mode, AttributeControllerIndex::OverscanColor),
+        u8::from(Color16::Blue)
+    );
+    vga.attribute_controller_registers
+        .unblank_screen(emulation_mode);
+    vga.set_border_color(Color16::Black);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_split_screen() {
+    serial_print!("set split screen... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode640x480x16);
+    let emulation_mode = vga.get_emulation_mode();
+    vga.set_split_screen(Some(0x2AB));
+    let crtc = &mut vga.crtc_controller_registers;
+    assert_eq!(
+        crtc.read(emulation_mode, CrtcControllerIndex::LineCompare),
+        0xAB
+    );
+    assert_eq!(
+        crtc.read(emulation_mode, CrtcControllerIndex::Overflow) & 0x10,
+        0x00
+    );
+    assert_eq!(
+        crtc.read(emulation_mode, CrtcControllerIndex::MaximumScanLine) & 0x40,
+        0x40
+    );
+
+    vga.set_split_screen(None);
+    check_registers(&mut vga, &MODE_640X480X16_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_pixel_pan() {
+    serial_print!("set pixel pan... ");
+
+    let mut vga = VGA.lock();
+    let emulation_mode = vga.get_emulation_mode();
+    for &(video_mode, pixels, value) in [
+        (VideoMode::Mode80x25, 0, 0x08),
+        (VideoMode::Mode80x25, 3, 0x02),
+        (VideoMode::Mode320x200x256, 3, 0x06),
+        (VideoMode::Mode640x480x16, 5, 0x05),
+    ]
+    .iter()
+    {
+        vga.set_video_mode(video_mode);
+        vga.set_pixel_pan(pixels);
+        assert_eq!(
+            vga.attribute_controller_registers.read(
+                emulation_mode,
+                AttributeControllerIndex::HorizontalPixelPanning
+            ),
+            value
+        );
+        vga.attribute_controller_registers
+            .unblank_screen(emulation_mode);
+    }
+    vga.set_pixel_pan(0);
+    check_registers(&mut vga, &MODE_640X480X16_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn blank_screen() {
+    serial_print!("blank screen... ");
+
+    let mut vga = VGA.lock();
+    vga.blank_screen();
+    assert_eq!(
+        vga.sequencer_registers.read(SequencerIndex::ClockingMode) & 0x20,
+        0x20
+    );
+    vga.unblank_screen();
+    assert_eq!(
+        vga.sequencer_registers.read(SequencerIndex::ClockingMode) & 0x20,
+        0x00
+    );
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn rotate_range() {
+    serial_print!("rotate range... ");
+
+    let mut vga = VGA.lock();
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+    vga.color_palette_registers.set_color(16, 0x01, 0x02, 0x03);
+    vga.color_palette_registers.set_color(17, 0x04, 0x05, 0x06);
+    vga.color_palette_registers.set_color(18, 0x07, 0x08, 0x09);
+    vga.color_palette_registers.rotate_range(16, 3);
+    assert_eq!(
+        vga.color_palette_registers.read_color(16),
+        (0x07, 0x08, 0x09)
+    );
+    assert_eq!(
+        vga.color_palette_registers.read_color(17),
+        (0x01, 0x02, 0x03)
+    );
+    assert_eq!(
+        vga.color_palette_registers.read_color(18),
+        (0x04, 0x05, 0x06)
+    );
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_palette_color() {
+    serial_print!("set palette color... ");
+
+    let mut vga = VGA.lock();
+    let orange = PaletteColor::from_rgb888(0xFF, 0x80, 0x00);
+    vga.color_palette_registers.set_palette_color(16, orange);
+    assert_eq!(
+        vga.color_palette_registers.read_color(16),
+        (0x3F, 0x20, 0x00)
+    );
+
+    let mut palette = Palette::default();
+    palette.set(0, PaletteColor::new_clamped(0xFF, 0x10, 0x00));
+    palette.set(1, orange);
+    vga.color_palette_registers.load_palette_colors(&palette);
+    assert_eq!(
+        vga.color_palette_registers.read_color(0),
+        (0x3F, 0x10, 0x00)
+    );
+    assert_eq!(
+        vga.color_palette_registers.read_color(1),
+        (0x3F, 0x20, 0x00)
+    );
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn raw_register_access() {
+    serial_print!("raw register access... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode80x25);
+    let emulation_mode = vga.get_emulation_mode();
+
+    let clocking_mode = u8::from(SequencerIndex::ClockingMode);
+    assert_eq!(vga.sequencer_registers.read_raw(clocking_mode), 0x00);
+    let offset = u8::from(CrtcControllerIndex::Offset);
+    assert_eq!(
+        vga.crtc_controller_registers
+            .read_raw(emulation_mode, offset),
+        0x28
+    );
+    vga.crtc_controller_registers
+        .write_raw(emulation_mode, offset, 0x50);
+    assert_eq!(
+        vga.crtc_controller_registers
+            .read(emulation_mode, CrtcControllerIndex::Offset),
+        0x50
+    );
+    vga.set_video_mode(VideoMode::Mode80x25);
+    check_registers(&mut vga, &MODE_80X25_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_attribute_palette() {
+    serial_print!("set attribute palette... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode640x480x16);
+    let default = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x14, 0x07, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E,
+        0x3F,
+    ];
+    assert_eq!(vga.get_attribute_palette(), default);
+
+    let mut mapping = default;
+    mapping.rotate_left(1);
+    vga.set_attribute_palette(mapping);
+    assert_eq!(vga.get_attribute_palette(), mapping);
+
+    vga.set_attribute_palette(default);
+    check_registers(&mut vga, &MODE_640X480X16_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_mode_320x200x4() {
+    serial_print!("mode 320x200x4... ");
+
+    let mut vga = VGA.lock();
+    vga.set_video_mode(VideoMode::Mode320x200x4);
+    check_registers(&mut vga, &MODE_320X200X4_CONFIGURATION);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn draw_image_320x200x256() {
+    serial_print!("draw image 320x200x256... ");
+
+    let mut image = [0u8; 6 * 4];
+    for (i, pixel) in image.iter_mut().enumerate() {
+        *pixel = i as u8 + 1;
+    }
+    let mode = Graphics320x200x256::new();
+    mode.set_mode();
+    mode.clear_screen(0);
+    mode.draw_image((10, 20), 6, 4, &image);
+    mode.draw_image((317, 198), 6, 4, &image);
+
+    assert_eq!(mode.get_pixel(10, 20), Some(1));
+    assert_eq!(mode.get_pixel(15, 23), Some(24));
+    assert_eq!(mode.get_pixel(16, 23), Some(0));
+    assert_eq!(mode.get_pixel(10, 24), Some(0));
+    assert_eq!(mode.get_pixel(319, 199), Some(9));
+
+    serial_println!("[ok]");
+}
+
 #[test_case]
 fn set_mode_640x480x16() {
     serial_print!("mode 640x480x16... ");
@@ -77,6 +466,427 @@ fn set_mode_640x480x16() {
     serial_println!("[ok]");
 }
 
+#[test_case]
+fn set_mode_640x350x16() {
+    serial_print!("mode 640x350x16... ");
+
+    let mode = PlanarGraphics::<640, 350>::new();
+    mode.set_mode();
+    check_registers(&mut VGA.lock(), &MODE_640X350X16_CONFIGURATION);
+    mode.set_pixel(639, 349, Color16::LightRed);
+    assert_eq!(mode.get_pixel(639, 349), Some(Color16::LightRed));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_mode_320x200x16() {
+    serial_print!("mode 320x200x16... ");
+
+    let mode = PlanarGraphics::<320, 200>::new();
+    mode.set_mode();
+    check_registers(&mut VGA.lock(), &MODE_320X200X16_CONFIGURATION);
+    mode.set_pixel(319, 199, Color16::Cyan);
+    assert_eq!(mode.get_pixel(319, 199), Some(Color16::Cyan));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn clear_screen_640x480x16() {
+    serial_print!("clear screen 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.set_raster_op(RasterOp::Xor);
+    mode.clear_screen(Color16::Cyan);
+    assert_eq!(mode.get_raster_op(), RasterOp::Xor);
+    mode.set_raster_op(RasterOp::Replace);
+
+    for &(x, y) in [(0, 0), (7, 0), (8, 1), (320, 240), (639, 479)].iter() {
+        assert_eq!(mode.get_pixel(x, y), Some(Color16::Cyan));
+    }
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn clear_screen_tail_bytes() {
+    serial_print!("clear screen tail bytes... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.fill_rect((0, 0), 32, 1, Color16::White);
+    // A plane of 3 bytes is too short for a single 4 byte write.
+    PlanarGraphics::<8, 3>::new().clear_screen(Color16::Blue);
+    assert_eq!(mode.get_pixel(0, 0), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(23, 0), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(24, 0), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn draw_full_range_lines_640x480x16() {
+    serial_print!("draw full range lines 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+    mode.draw_horizontal_line(0, usize::MAX, 10, Color16::White);
+    mode.draw_vertical_line(20, usize::MAX, 0, Color16::Red);
+    assert_eq!(mode.get_pixel(0, 10), Some(Color16::White));
+    assert_eq!(mode.get_pixel(639, 10), Some(Color16::White));
+    assert_eq!(mode.get_pixel(20, 0), Some(Color16::Red));
+    assert_eq!(mode.get_pixel(20, 479), Some(Color16::Red));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn invert_region_640x480x16() {
+    serial_print!("invert region 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+    mode.fill_rect((3, 2), 10, 4, Color16::Blue);
+
+    mode.invert_region((5, 3, 20, 2));
+    assert_eq!(mode.get_pixel(5, 3), Some(Color16::Yellow));
+    assert_eq!(mode.get_pixel(24, 4), Some(Color16::White));
+    assert_eq!(mode.get_pixel(4, 3), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(5, 2), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(25, 4), Some(Color16::Black));
+    assert_eq!(mode.get_raster_op(), RasterOp::Replace);
+
+    mode.invert_region((5, 3, 20, 2));
+    assert_eq!(mode.get_pixel(5, 3), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(24, 4), Some(Color16::Black));
+
+    mode.invert_region_with_mask((0, 0, 1, 1), 0x7);
+    assert_eq!(mode.get_pixel(0, 0), Some(Color16::LightGrey));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn draw_string_wrapped_640x480x16() {
+    serial_print!("draw string wrapped 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+
+    // Full blocks make every pixel of a character visible.
+    let rect = Rect::new(0, 0, 24, 12);
+    mode.draw_string_wrapped(
+        rect,
+        "\u{2588}\u{2588} \u{2588}",
+        Color16::White,
+        TextAlignment::Right,
+    );
+    assert_eq!(mode.get_pixel(7, 0), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(0, 0), Some(Color16::White));
+    assert_eq!(mode.get_pixel(23, 7), Some(Color16::White));
+    assert_eq!(mode.get_pixel(15, 8), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(16, 8), Some(Color16::White));
+    assert_eq!(mode.get_pixel(23, 11), Some(Color16::White));
+    assert_eq!(mode.get_pixel(23, 12), Some(Color16::Black));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn draw_string_scaled_640x480x16() {
+    serial_print!("draw string scaled 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+
+    mode.draw_string_scaled(10, 10, "\u{2588}\u{2588}", Color16::White, 3);
+    assert_eq!(mode.get_pixel(10, 10), Some(Color16::White));
+    assert_eq!(mode.get_pixel(57, 33), Some(Color16::White));
+    assert_eq!(mode.get_pixel(58, 10), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(10, 34), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(9, 10), Some(Color16::Black));
+
+    mode.clear_screen(Color16::Black);
+    mode.set_clip(Some((0, 0, 20, 20)));
+    mode.draw_string_scaled(10, 10, "\u{2588}", Color16::White, 2);
+    mode.set_clip(None);
+    assert_eq!(mode.get_pixel(19, 19), Some(Color16::White));
+    assert_eq!(mode.get_pixel(20, 10), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(10, 20), Some(Color16::Black));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn draw_string_cp437_640x480x16() {
+    serial_print!("draw string cp437 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+    mode.draw_string(16, 8, "\u{2554}\u{2550}", Color16::White);
+
+    // The double lines of the corner run on into the horizontal line.
+    for x in 18..32 {
+        assert_eq!(mode.get_pixel(x, 10), Some(Color16::White));
+    }
+    assert_eq!(mode.get_pixel(17, 10), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(18, 11), Some(Color16::White));
+    assert_eq!(mode.get_pixel(20, 11), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(24, 11), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(24, 12), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn graphics_text_writer_640x480x16() {
+    serial_print!("graphics text writer 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+
+    let mut writer = GraphicsTextWriter::new(mode.clone(), Color16::White, Color16::Blue);
+    write!(writer, "ab\n").unwrap();
+    writer.write_char('\u{2588}').unwrap();
+    assert_eq!(writer.get_position(), (1, 1));
+    assert_eq!(mode.get_pixel(0, 8), Some(Color16::White));
+    assert_eq!(mode.get_pixel(7, 15), Some(Color16::White));
+    assert_eq!(mode.get_pixel(8, 8), Some(Color16::Black));
+    // The background of each cell is filled.
+    assert_eq!(mode.get_pixel(7, 7), Some(Color16::Blue));
+
+    // A new line on the last row scrolls the screen up by one row of cells.
+    writer.set_position(0, GraphicsTextWriter::ROWS - 1);
+    write!(writer, "\u{2588}\n").unwrap();
+    assert_eq!(writer.get_position(), (0, GraphicsTextWriter::ROWS - 1));
+    assert_eq!(mode.get_pixel(0, 464), Some(Color16::White));
+    assert_eq!(mode.get_pixel(0, 472), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(8, 0), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn write_byte_column_640x480x16() {
+    serial_print!("write byte column 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+
+    mode.write_byte_column(2, 5, [0xF0, 0x0F, 0x81, 0x00]);
+    assert_eq!(mode.get_pixel(16, 5), Some(Color16::Magenta));
+    assert_eq!(mode.get_pixel(17, 5), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(20, 5), Some(Color16::Green));
+    assert_eq!(mode.get_pixel(23, 5), Some(Color16::Brown));
+    assert_eq!(mode.get_pixel(24, 5), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(16, 6), Some(Color16::Black));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn frame_buffer_ptr_640x480x16() {
+    serial_print!("frame buffer ptr 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    let frame_buffer = unsafe { mode.frame_buffer_ptr() };
+    assert_eq!(frame_buffer, mode.get_frame_buffer());
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_pixel_rgb_640x480x16() {
+    serial_print!("set pixel rgb 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+    assert_eq!(mode.color_matching(), ColorMatching::Nearest);
+    mode.set_pixel_rgb(10, 10, 0xFF, 0x50, 0x58);
+    mode.set_pixel_rgb(11, 10, 0xF0, 0xF8, 0xFF);
+    assert_eq!(mode.get_pixel(10, 10), Some(Color16::LightRed));
+    assert_eq!(mode.get_pixel(11, 10), Some(Color16::White));
+
+    // A color halfway between black and dark grey is spread over both.
+    mode.set_color_matching(ColorMatching::Ordered);
+    for y in 20..24 {
+        for x in 20..24 {
+            mode.set_pixel_rgb(x, y, 0x2A, 0x2A, 0x2A);
+        }
+    }
+    let pixels = (0..16).map(|i| mode.get_pixel(20 + i % 4, 20 + i / 4));
+    assert!(pixels.clone().any(|color| color == Some(Color16::Black)));
+    assert!(pixels.clone().any(|color| color == Some(Color16::DarkGrey)));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn clear_rect_640x480x16() {
+    serial_print!("clear rect 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::White);
+    mode.set_raster_op(RasterOp::Or);
+    mode.clear_rect(Rect::new(5, 10, 20, 4));
+    assert_eq!(mode.get_raster_op(), RasterOp::Or);
+    mode.set_raster_op(RasterOp::Replace);
+    assert_eq!(mode.get_pixel(5, 10), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(24, 13), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(4, 10), Some(Color16::White));
+    assert_eq!(mode.get_pixel(25, 13), Some(Color16::White));
+    assert_eq!(mode.get_pixel(5, 14), Some(Color16::White));
+
+    // Only the part inside of the clip rectangle and the screen is cleared.
+    mode.set_clip(Some((600, 0, 40, 480)));
+    mode.clear_rect(Rect::new(590, 470, 100, 100));
+    mode.set_clip(None);
+    assert_eq!(mode.get_pixel(599, 479), Some(Color16::White));
+    assert_eq!(mode.get_pixel(600, 470), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(639, 479), Some(Color16::Black));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn planar_graphics_640x400() {
+    serial_print!("planar graphics 640x400... ");
+
+    // 640x400 shares the layout of 640x480, but stops 80 rows earlier.
+    let full = Graphics640x480x16::new();
+    full.set_mode();
+    full.clear_screen(Color16::White);
+    let mode = PlanarGraphics::<640, 400>::new();
+    mode.clear_screen(Color16::Blue);
+    mode.draw_line((0, 0), (639, 399), Color16::Yellow);
+    assert_eq!(mode.get_pixel(639, 399), Some(Color16::Yellow));
+    assert_eq!(mode.get_pixel(639, 0), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(0, 400), None);
+    assert_eq!(full.get_pixel(0, 400), Some(Color16::White));
+    assert_eq!(full.get_pixel(639, 479), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn blit_surface_640x480x16() {
+    serial_print!("blit surface 640x480x16... ");
+
+    let mut buffer = [0; OffscreenSurface::required_size(9, 4)];
+    let mut surface = OffscreenSurface::new(&mut buffer, 9, 4);
+    surface.clear(Color16::Blue);
+    Surface::set_pixel(&mut surface, 3, 1, Color16::Red);
+    Surface::set_pixel(&mut surface, 8, 3, Color16::Black);
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::White);
+    mode.blit_surface(&surface, Rect::new(3, 1, 10, 10), (101, 50));
+    assert_eq!(mode.get_pixel(101, 50), Some(Color16::Red));
+    assert_eq!(mode.get_pixel(106, 52), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(100, 50), Some(Color16::White));
+    assert_eq!(mode.get_pixel(107, 52), Some(Color16::White));
+    assert_eq!(mode.get_pixel(101, 53), Some(Color16::White));
+
+    mode.blit_surface_keyed(&surface, surface.bounds(), (200, 0), Color16::Blue);
+    assert_eq!(mode.get_pixel(203, 1), Some(Color16::Red));
+    assert_eq!(mode.get_pixel(208, 3), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(200, 0), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_pixels_640x480x16() {
+    serial_print!("set pixels 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Black);
+    mode.set_pixels(&[
+        (0, 0, Color16::Red),
+        (8, 0, Color16::Green),
+        (9, 0, Color16::Blue),
+        (639, 479, Color16::White),
+        (640, 0, Color16::Yellow),
+        (0, usize::MAX, Color16::Yellow),
+    ]);
+
+    assert_eq!(mode.get_pixel(0, 0), Some(Color16::Red));
+    assert_eq!(mode.get_pixel(1, 0), Some(Color16::Black));
+    assert_eq!(mode.get_pixel(8, 0), Some(Color16::Green));
+    assert_eq!(mode.get_pixel(9, 0), Some(Color16::Blue));
+    assert_eq!(mode.get_pixel(639, 479), Some(Color16::White));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn fill_rect_gradient_640x480x16() {
+    serial_print!("fill rect gradient 640x480x16... ");
+
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Red);
+    let rect = Rect::new(3, 0, 33, 4);
+    mode.fill_rect_gradient(
+        rect,
+        Color16::Black,
+        Color16::White,
+        GradientDirection::Horizontal,
+    );
+
+    assert_eq!(mode.get_pixel(2, 0), Some(Color16::Red));
+    assert_eq!(mode.get_pixel(36, 0), Some(Color16::Red));
+    for y in 0..4 {
+        assert_eq!(mode.get_pixel(3, y), Some(Color16::Black));
+        assert_eq!(mode.get_pixel(35, y), Some(Color16::White));
+    }
+    // Halfway along, half of each 4x4 block has switched over.
+    let white = (17..21)
+        .flat_map(|x| (0..4).map(move |y| (x, y)))
+        .filter(|&(x, y)| mode.get_pixel(x, y) == Some(Color16::White))
+        .count();
+    assert!((6..=10).contains(&white));
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn dump_640x480x16() {
+    serial_print!("dump 640x480x16... ");
+
+    static mut SCREEN: [u8; Graphics640x480x16::DUMP_SIZE] = [0; Graphics640x480x16::DUMP_SIZE];
+    let screen = unsafe { &mut *&raw mut SCREEN };
+    let mode = Graphics640x480x16::new();
+    mode.set_mode();
+    mode.clear_screen(Color16::Blue);
+    mode.set_pixel(1, 0, Color16::Red);
+    mode.fill_rect((8, 1), 8, 1, Color16::White);
+    mode.dump_to(screen);
+
+    assert_eq!(screen[0], 0x14);
+    assert_eq!(screen[1], 0x11);
+    for i in 0..4 {
+        assert_eq!(screen[320 + 4 + i], 0xFF);
+    }
+    assert_eq!(screen[Graphics640x480x16::DUMP_SIZE - 1], 0x11);
+
+    serial_println!("[ok]");
+}
+
 #[test_case]
 fn load_palette() {
     serial_print!("load palette... ");
@@ -93,6 +903,68 @@ fn load_palette() {
     serial_println!("[ok]");
 }
 
+#[test_case]
+fn read_color() {
+    serial_print!("read color... ");
+
+    let mut vga = VGA.lock();
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+
+    for index in 0..=255u8 {
+        let offset = usize::from(index) * 3;
+        assert_eq!(
+            vga.color_palette_registers.read_color(index),
+            (
+                DEFAULT_PALETTE[offset],
+                DEFAULT_PALETTE[offset + 1],
+                DEFAULT_PALETTE[offset + 2]
+            )
+        );
+    }
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_color() {
+    serial_print!("set color... ");
+
+    let mut vga = VGA.lock();
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+    vga.color_palette_registers
+        .set_color(0x10, 0x3F, 0x15, 0xC0);
+    assert_eq!(
+        vga.color_palette_registers.read_color(0x10),
+        (0x3F, 0x15, 0x00)
+    );
+    vga.color_palette_registers.load_palette(&DEFAULT_PALETTE);
+
+    serial_println!("[ok]");
+}
+
+#[test_case]
+fn set_raster_op() {
+    serial_print!("set raster op... ");
+
+    let mut vga = VGA.lock();
+    for raster_op in [
+        RasterOp::And,
+        RasterOp::Or,
+        RasterOp::Xor,
+        RasterOp::Replace,
+    ]
+    .iter()
+    {
+        vga.graphics_controller_registers.set_raster_op(*raster_op);
+        assert_eq!(
+            vga.graphics_controller_registers.get_raster_op(),
+            *raster_op
+        );
+    }
+
+    serial_println!("[ok]");
+}
+
 fn check_registers(vga: &mut Vga, configuration: &VgaConfiguration) {
     let emulation_mode = vga.get_emulation_mode();
     assert_eq!(